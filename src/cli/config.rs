@@ -1,9 +1,10 @@
 use clap::ArgMatches;
 
-use crate::templates::config::TemplatesMetadata;
+use crate::{projects::config::ProjectsMetadata, templates::config::TemplatesMetadata};
 
 pub struct CliMetadata<'a> {
     pub templates_meta: &'a TemplatesMetadata,
+    pub projects_meta: &'a ProjectsMetadata,
 }
 
 pub struct CliParserOptions<'a> {
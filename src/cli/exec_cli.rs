@@ -0,0 +1,148 @@
+use std::{
+    io::{self, Write},
+    path::Path,
+    process::{self, Command},
+    time::Duration,
+};
+
+use console::{Emoji, style};
+
+use super::{
+    config::CliParserOptions,
+    projects_cli::{load_projects, project_filter},
+};
+use crate::{
+    projects::{
+        config::RegisteredProject,
+        exec::{ProjectRun, run_in_projects},
+    },
+    scripts::{ScriptStatus, Shell},
+};
+
+/// Builds the command to run, in a shell when it's a single argument like `"cargo update && cargo
+/// test"` and directly otherwise
+fn build_command(arguments: &[String], shell: Option<&String>) -> Command {
+    match arguments {
+        [script] => shell
+            .map(|shell| Shell::from_name(shell))
+            .unwrap_or_default()
+            .inline(script),
+        [program, arguments @ ..] => {
+            let mut command = Command::new(program);
+            command.args(arguments);
+            command
+        }
+        [] => unreachable!("the command is required"),
+    }
+}
+
+/// Prints the output of a run under a heading naming its project
+fn print_run(run: &ProjectRun) {
+    println!(
+        "\n{} {} {}",
+        style("──").dim(),
+        style(&run.project.name).bold(),
+        style(&run.project.path).dim()
+    );
+
+    let outcome = match &run.output {
+        Ok(output) => {
+            let _ = io::stdout().write_all(&output.stdout);
+            let _ = io::stderr().write_all(&output.stderr);
+            match output.status {
+                ScriptStatus::Success => String::new(),
+                ScriptStatus::Failed => String::from("Failed."),
+                ScriptStatus::TimedOut => String::from("Timed out."),
+                ScriptStatus::Interrupted => String::from("Interrupted."),
+            }
+        }
+        Err(error) => format!("Unable to run the command: {}.", error),
+    };
+
+    if outcome.is_empty() {
+        println!(
+            "{} {}",
+            style(Emoji("✅", "✔")).green(),
+            style("Done.").green()
+        );
+    } else {
+        eprintln!("{} {}", style(Emoji("❌", "𝗑")).red(), style(outcome).red());
+    }
+}
+
+pub fn run_exec_cli_args(options: &CliParserOptions) {
+    let exec_cmd = options.matches.subcommand_matches("exec").unwrap();
+    let filter = project_filter(exec_cmd);
+    let arguments = exec_cmd
+        .get_many::<String>("command")
+        .unwrap()
+        .cloned()
+        .collect::<Vec<String>>();
+    let shell = exec_cmd.get_one::<String>("shell");
+    let timeout = exec_cmd
+        .get_one::<u64>("timeout")
+        .map(|seconds| Duration::from_secs(*seconds));
+
+    let (projects, skipped): (Vec<RegisteredProject>, Vec<RegisteredProject>) =
+        load_projects(options)
+            .registry
+            .into_iter()
+            .filter(|project| filter.matches(project))
+            .partition(|project| project.archived.is_none() && Path::new(&project.path).is_dir());
+
+    for project in &skipped {
+        println!(
+            "{} {}, its folder is missing or archived",
+            style("Skipped").yellow().bold(),
+            project.name
+        );
+    }
+    if projects.is_empty() {
+        println!("No projects found.");
+        return;
+    }
+
+    let mut passed = 0;
+    let mut failed = vec![];
+    let mut interrupted = false;
+    run_in_projects(
+        &projects,
+        *exec_cmd.get_one::<usize>("parallel").unwrap(),
+        timeout,
+        |_| build_command(&arguments, shell),
+        |run| {
+            print_run(&run);
+            match run.status() {
+                ScriptStatus::Success => passed += 1,
+                ScriptStatus::Interrupted => {
+                    interrupted = true;
+                    failed.push(run.project.name.clone());
+                }
+                ScriptStatus::Failed | ScriptStatus::TimedOut => {
+                    failed.push(run.project.name.clone())
+                }
+            }
+        },
+    );
+
+    let not_run = projects.len() - passed - failed.len();
+    println!(
+        "\n{} passed, {} failed{}",
+        style(passed).green().bold(),
+        style(failed.len()).red().bold(),
+        if not_run > 0 {
+            format!(", {} not run", style(not_run).yellow().bold())
+        } else {
+            String::new()
+        }
+    );
+    if !failed.is_empty() {
+        eprintln!("Failed in {}.", failed.join(", "));
+    }
+
+    if interrupted || not_run > 0 {
+        process::exit(130);
+    } else if !failed.is_empty() {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,64 @@
+use std::{path, process};
+
+use console::{Emoji, style};
+
+use super::config::CliParserOptions;
+use crate::{
+    generator::{ProjectAnswers, ProjectGenerator},
+    templates::resolver::{ResolvedTemplate, TemplateResolver},
+};
+
+/// Resolves fragments by name, alias or path to their folders, exiting when one can't be found
+pub fn resolve_fragments<'a, Names: Iterator<Item = &'a String>>(
+    resolver: &TemplateResolver,
+    names: Names,
+) -> Vec<String> {
+    names
+        .filter_map(|name| match resolver.resolve(name) {
+            Ok(ResolvedTemplate::Directory(fragment_path)) => Some(fragment_path),
+            Ok(ResolvedTemplate::Empty) => None,
+            Err(error) => {
+                eprintln!("{}", error);
+                process::exit(1);
+            }
+        })
+        .collect()
+}
+
+pub fn run_add_fragment_cli_args(options: &CliParserOptions) {
+    let add_cmd = options.matches.subcommand_matches("add").unwrap();
+
+    let project_path = path::absolute(add_cmd.get_one::<String>("path").unwrap()).unwrap();
+    if !project_path.is_dir() {
+        eprintln!("The project {} does not exist!", project_path.display());
+        process::exit(1);
+    }
+
+    let resolver = TemplateResolver::load(options.metadata.templates_meta);
+    let fragments = resolve_fragments(
+        &resolver,
+        add_cmd.get_many::<String>("fragments").unwrap_or_default(),
+    );
+
+    let answers = ProjectAnswers {
+        name: project_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        output: project_path.to_str().unwrap().to_string(),
+        ..ProjectAnswers::default()
+    };
+
+    if let Err(error) =
+        ProjectGenerator::add_fragments(&fragments, &answers, &resolver, &project_path)
+    {
+        eprintln!("{}", error);
+        process::exit(error.exit_code());
+    }
+
+    println!(
+        "\n{} {}",
+        style(Emoji("🧩", "✔")).green().bright(),
+        style(format!("Added to {}", project_path.display())).bold(),
+    );
+}
@@ -1,16 +1,29 @@
 use clap::{Arg, ArgAction, ArgMatches, Command, builder::BoolValueParser, command};
 use config::CliParserOptions;
+use exec_cli::run_exec_cli_args;
+use fragment_cli::run_add_fragment_cli_args;
+use open_cli::{run_open_cli_args, run_path_cli_args};
 use project_cli::run_new_project_cli_args;
+use projects_cli::run_projects_cli_args;
+use run_cli::run_task_cli_args;
+use status_cli::run_status_cli_args;
 use template_cli::run_new_template_cli_args;
 
+use crate::projects::config::parse_metadata_entry;
+
 pub mod config;
+pub mod exec_cli;
+pub mod fragment_cli;
+pub mod open_cli;
 pub mod project_cli;
+pub mod projects_cli;
+pub mod run_cli;
+pub mod status_cli;
 pub mod template_cli;
 pub mod theme;
 
 pub fn register_cli_args() -> ArgMatches {
-    let arg_matches =
-        command!()
+    command!()
             .subcommand(
                 Command::new("new")
                     .subcommand(
@@ -50,14 +63,40 @@ pub fn register_cli_args() -> ArgMatches {
                                 Arg::new("template")
                                     .short('t')
                                     .long("template")
-                                    .help("Sets the path to the template for the new project."),
+                                    .help("Sets the template for the new project by name, alias or path, or `none`."),
+                            )
+                            .arg(
+                                Arg::new("fragments")
+                                    .long("with")
+                                    .value_delimiter(',')
+                                    .action(ArgAction::Append)
+                                    .help("Applies fragments, by name, alias or path, on top of the template, e.g. `docker,ci`."),
+                            )
+                            .arg(
+                                Arg::new("tags")
+                                    .long("tag")
+                                    .value_delimiter(',')
+                                    .action(ArgAction::Append)
+                                    .help("Tags the project in the projects registry, e.g. `backend,team-payments`."),
+                            )
+                            .arg(
+                                Arg::new("owner")
+                                    .long("owner")
+                                    .help("The person or team responsible for the project."),
                             )
+                            .arg(metadata_arg().help("Records a `key=value` detail about the project in the projects registry."))
                             .arg(
                                 Arg::new("create_empty")
                                     .short('e')
                                     .long("empty")
                                     .value_parser(BoolValueParser::new())
                                     .help("Creates an empty project."),
+                            )
+                            .arg(
+                                Arg::new("keep_on_failure")
+                                    .long("keep-on-failure")
+                                    .action(ArgAction::SetTrue)
+                                    .help("Keeps the partially created project when creation fails."),
                             ),
                     )
                     .subcommand(
@@ -104,7 +143,7 @@ pub fn register_cli_args() -> ArgMatches {
                                 Arg::new("exclude_paths")
                                     .short('p')
                                     .long("exclude-paths")
-                                    .help("Exclude certain paths when copying the template.")
+                                    .help("Exclude paths matching gitignore-style patterns, e.g. `**/target` or `*.log`, when copying the template.")
                                     .action(ArgAction::Append),
                             )
                             .arg(
@@ -113,25 +152,357 @@ pub fn register_cli_args() -> ArgMatches {
                                     .long("script")
                                     .help("Add initialisation scripts to run when the project is created.")
                                     .action(ArgAction::Append),
+                            )
+                            .arg(
+                                Arg::new("aliases")
+                                    .long("alias")
+                                    .help("Add another name the template can be referred to by.")
+                                    .action(ArgAction::Append),
+                            )
+                            .arg(
+                                Arg::new("fragment")
+                                    .long("fragment")
+                                    .action(ArgAction::SetTrue)
+                                    .help("Creates a fragment, which adds files and scripts to projects instead of creating them."),
+                            )
+                            .arg(
+                                Arg::new("extends")
+                                    .long("extends")
+                                    .help("The template, by name, alias or path, that this template is layered on top of."),
+                            )
+                            .arg(
+                                Arg::new("no_gitignore")
+                                    .long("no-gitignore")
+                                    .action(ArgAction::SetTrue)
+                                    .help("Copy files ignored by the source's .gitignore and .ignore files."),
+                            )
+                            .arg(
+                                Arg::new("symlinks")
+                                    .long("symlinks")
+                                    .value_parser(["preserve", "follow", "skip"])
+                                    .help("Whether symbolic links are preserved, followed or skipped when copying."),
+                            )
+                            .arg(
+                                Arg::new("preserve_timestamps")
+                                    .long("preserve-timestamps")
+                                    .action(ArgAction::SetTrue)
+                                    .help("Keep the modification times of copied files."),
                             ),
                     ),
             )
-            .get_matches();
+            .subcommand(
+                Command::new("add")
+                    .about("Adds fragments to an existing project")
+                    .arg(
+                        Arg::new("fragments")
+                            .required(true)
+                            .num_args(1..)
+                            .value_delimiter(',')
+                            .help("The fragments to add by name, alias or path, e.g. `docker,ci`."),
+                    )
+                    .arg(
+                        Arg::new("path")
+                            .short('p')
+                            .long("path")
+                            .default_value(".")
+                            .help("The project the fragments are added to."),
+                    ),
+            )
+            .subcommand(
+                Command::new("projects")
+                    .about("Manages the projects edna knows about")
+                    .arg_required_else_help(true)
+                    .subcommand(
+                        Command::new("list")
+                            .about("Lists the registered projects")
+                            .args(filter_args("lists"))
+                            .arg(
+                                Arg::new("since")
+                                    .long("since")
+                                    .help("Only lists projects created on or after this date, e.g. 2024-05-31."),
+                            )
+                            .arg(
+                                Arg::new("missing")
+                                    .long("missing")
+                                    .action(ArgAction::SetTrue)
+                                    .help("Only lists projects whose folder no longer exists."),
+                            )
+                            .args(listing_args()),
+                    )
+                    .subcommand(
+                        Command::new("search")
+                            .about("Finds projects by name, description or path")
+                            .arg(
+                                Arg::new("text")
+                                    .required(true)
+                                    .help("The text to look for, ignoring case."),
+                            )
+                            .args(listing_args()),
+                    )
+                    .subcommand(
+                        Command::new("import")
+                            .about("Registers existing projects that weren't created by edna")
+                            .arg(
+                                Arg::new("path")
+                                    .required_unless_present("scan")
+                                    .conflicts_with("scan")
+                                    .help("The root of the project to register."),
+                            )
+                            .arg(
+                                Arg::new("scan")
+                                    .long("scan")
+                                    .help("Registers every project found in this folder."),
+                            )
+                            .arg(
+                                Arg::new("depth")
+                                    .long("depth")
+                                    .value_parser(clap::value_parser!(usize))
+                                    .default_value("2")
+                                    .help("How many levels of folders below the scanned one are looked into."),
+                            ),
+                    )
+                    .subcommand(
+                        Command::new("archive")
+                            .about("Compresses a project into a .tar.gz archive and removes its folder")
+                            .arg(project_arg())
+                            .arg(
+                                Arg::new("output")
+                                    .short('o')
+                                    .long("output")
+                                    .help("Where the archive is written, next to the project by default."),
+                            )
+                            .arg(
+                                Arg::new("keep")
+                                    .long("keep")
+                                    .action(ArgAction::SetTrue)
+                                    .help("Keeps the project's folder once it's archived."),
+                            ),
+                    )
+                    .subcommand(
+                        Command::new("tag")
+                            .about("Tags a project and sets its owner and metadata")
+                            .arg(project_arg())
+                            .arg(
+                                Arg::new("tags")
+                                    .num_args(0..)
+                                    .help("The tags to add, e.g. `backend team-payments`."),
+                            )
+                            .arg(
+                                Arg::new("remove")
+                                    .short('r')
+                                    .long("remove")
+                                    .action(ArgAction::SetTrue)
+                                    .help("Removes the tags instead of adding them."),
+                            )
+                            .arg(
+                                Arg::new("owner")
+                                    .long("owner")
+                                    .help("Sets the person or team responsible for the project, or clears it when empty."),
+                            )
+                            .arg(metadata_arg().help("Sets a `key=value` detail about the project."))
+                            .arg(
+                                Arg::new("unset")
+                                    .long("unset")
+                                    .action(ArgAction::Append)
+                                    .help("Removes the metadata with this key."),
+                            ),
+                    )
+                    .subcommand(
+                        Command::new("rename")
+                            .about("Changes the name a project is registered under")
+                            .arg(project_arg())
+                            .arg(
+                                Arg::new("new_name")
+                                    .required(true)
+                                    .help("The project's new name."),
+                            ),
+                    )
+                    .subcommand(
+                        Command::new("move")
+                            .about("Moves a project's folder and updates the registry")
+                            .arg(project_arg())
+                            .arg(
+                                Arg::new("destination")
+                                    .required(true)
+                                    .help("The project's new path, or an existing folder to move it into."),
+                            ),
+                    )
+                    .subcommand(
+                        Command::new("delete")
+                            .about("Deletes a project's folder and removes it from the registry")
+                            .arg(project_arg())
+                            .arg(
+                                Arg::new("yes")
+                                    .short('y')
+                                    .long("yes")
+                                    .action(ArgAction::SetTrue)
+                                    .help("Deletes the project, and its archive if it has one, without asking first."),
+                            )
+                            .arg(
+                                Arg::new("keep_files")
+                                    .long("keep-files")
+                                    .action(ArgAction::SetTrue)
+                                    .help("Only removes the project from the registry."),
+                            ),
+                    ),
+            )
+            .subcommand(
+                Command::new("open")
+                    .about("Opens a registered project in your editor")
+                    .arg(project_arg()),
+            )
+            .subcommand(
+                Command::new("path")
+                    .about("Prints the path of a registered project, e.g. for `cd $(edna path api)`")
+                    .arg(project_arg()),
+            )
+            .subcommand(
+                Command::new("exec")
+                    .about("Runs a command in every registered project that matches, e.g. `edna exec --tag backend -- cargo update`")
+                    .args(filter_args("runs in"))
+                    .arg(
+                        Arg::new("parallel")
+                            .short('j')
+                            .long("parallel")
+                            .value_parser(clap::value_parser!(usize))
+                            .default_value("1")
+                            .help("How many projects the command runs in at once."),
+                    )
+                    .arg(
+                        Arg::new("shell")
+                            .long("shell")
+                            .help("The shell a command given as a single argument runs in, e.g. `bash`."),
+                    )
+                    .arg(
+                        Arg::new("timeout")
+                            .long("timeout")
+                            .value_parser(clap::value_parser!(u64))
+                            .help("Stops the command in a project after this many seconds."),
+                    )
+                    .arg(
+                        Arg::new("command")
+                            .required(true)
+                            .num_args(1..)
+                            .last(true)
+                            .help("The command to run. A single argument, e.g. `\"cargo update && cargo test\"`, runs in a shell."),
+                    ),
+            )
+            .subcommand(
+                Command::new("run")
+                    .about("Runs one of the tasks in the current project's edna.config.json, or lists them")
+                    .arg(Arg::new("task").help("The task to run, e.g. `build` or `test`.")),
+            )
+            .subcommand(
+                Command::new("status")
+                    .about("Shows the git state, template version and size of every registered project")
+                    .arg(
+                        Arg::new("json")
+                            .long("json")
+                            .action(ArgAction::SetTrue)
+                            .help("Prints the statuses as JSON."),
+                    ),
+            )
+            .get_matches()
+}
+
+/// The registered project a command acts on
+fn project_arg() -> Arg {
+    Arg::new("project")
+        .required(true)
+        .help("The name of the project, which may be partial.")
+}
+
+/// A repeatable `--meta key=value` option
+fn metadata_arg() -> Arg {
+    Arg::new("metadata")
+        .long("meta")
+        .value_parser(parse_metadata_entry)
+        .action(ArgAction::Append)
+}
+
+/// The options that narrow down the projects a command acts on, described by what the command
+/// does with them, e.g. `lists`
+fn filter_args(action: &str) -> [Arg; 4] {
+    [
+        Arg::new("template")
+            .short('t')
+            .long("template")
+            .help(format!(
+                "Only {} projects created from this template.",
+                action
+            )),
+        Arg::new("tags")
+            .long("tag")
+            .action(ArgAction::Append)
+            .help(format!("Only {} projects with this tag.", action)),
+        Arg::new("owner").long("owner").help(format!(
+            "Only {} projects owned by this person or team.",
+            action
+        )),
+        metadata_arg().help(format!(
+            "Only {} projects with this `key=value` metadata.",
+            action
+        )),
+    ]
+}
 
-    arg_matches
+/// The options shared by the commands that list projects
+fn listing_args() -> [Arg; 3] {
+    [
+        Arg::new("sort")
+            .long("sort")
+            .value_parser(["name", "created", "path", "template"])
+            .help("Sorts the projects by name (the default), created date, path or template."),
+        Arg::new("reverse")
+            .long("reverse")
+            .action(ArgAction::SetTrue)
+            .help("Lists the projects in reverse order."),
+        Arg::new("json")
+            .long("json")
+            .action(ArgAction::SetTrue)
+            .help("Prints the projects as JSON."),
+    ]
 }
 
 pub fn parse_cli_args(options: CliParserOptions) {
+    if options.matches.subcommand_matches("add").is_some() {
+        run_add_fragment_cli_args(&options);
+    }
+
+    if options.matches.subcommand_matches("projects").is_some() {
+        run_projects_cli_args(&options);
+    }
+
+    if options.matches.subcommand_matches("open").is_some() {
+        run_open_cli_args(&options);
+    }
+
+    if options.matches.subcommand_matches("path").is_some() {
+        run_path_cli_args(&options);
+    }
+
+    if options.matches.subcommand_matches("exec").is_some() {
+        run_exec_cli_args(&options);
+    }
+
+    if options.matches.subcommand_matches("run").is_some() {
+        run_task_cli_args(&options);
+    }
+
+    if options.matches.subcommand_matches("status").is_some() {
+        run_status_cli_args(&options);
+    }
+
     if let Some(new_cmd) = options.matches.subcommand_matches("new") {
-        if let Some(_) = new_cmd.subcommand_matches("project") {
-            return run_new_project_cli_args(&CliParserOptions {
-                metadata: &options.metadata,
-                matches: &new_cmd,
+        if new_cmd.subcommand_matches("project").is_some() {
+            run_new_project_cli_args(&CliParserOptions {
+                metadata: options.metadata,
+                matches: new_cmd,
             });
-        } else if let Some(_) = new_cmd.subcommand_matches("template") {
-            return run_new_template_cli_args(&CliParserOptions {
-                metadata: &options.metadata,
-                matches: &new_cmd,
+        } else if new_cmd.subcommand_matches("template").is_some() {
+            run_new_template_cli_args(&CliParserOptions {
+                metadata: options.metadata,
+                matches: new_cmd,
             });
         }
     }
@@ -0,0 +1,51 @@
+use std::{env, process};
+
+use super::{
+    config::CliParserOptions,
+    projects_cli::{load_projects, select_project},
+};
+use crate::projects::config::ProjectsConfig;
+
+/// The editor set in the projects registry, then `$VISUAL`, then `$EDITOR`
+fn configured_editor(config: &ProjectsConfig) -> Option<String> {
+    config
+        .editor
+        .clone()
+        .or_else(|| env::var("VISUAL").ok())
+        .or_else(|| env::var("EDITOR").ok())
+        .filter(|editor| !editor.trim().is_empty())
+}
+
+pub fn run_open_cli_args(options: &CliParserOptions) {
+    let open_cmd = options.matches.subcommand_matches("open").unwrap();
+    let config = load_projects(options);
+    let project = select_project(&config, open_cmd);
+
+    let Some(editor) = configured_editor(&config) else {
+        eprintln!(
+            "No editor is configured. Set $VISUAL or $EDITOR, or `editor` in the projects registry."
+        );
+        process::exit(1);
+    };
+
+    // Editors are often set with arguments, like `code --wait`
+    let mut words = editor.split_whitespace();
+    let status = process::Command::new(words.next().unwrap())
+        .args(words)
+        .arg(&project.path)
+        .current_dir(&project.path)
+        .status()
+        .unwrap_or_else(|error| {
+            eprintln!("Unable to launch {}: {}.", editor, error);
+            process::exit(1);
+        });
+
+    process::exit(status.code().unwrap_or(1));
+}
+
+pub fn run_path_cli_args(options: &CliParserOptions) {
+    let path_cmd = options.matches.subcommand_matches("path").unwrap();
+    let project = select_project(&load_projects(options), path_cmd);
+
+    println!("{}", project.path);
+}
@@ -1,249 +1,212 @@
-use std::{
-    fs,
-    io::{self, Write},
-    path::{self, PathBuf},
-    process::{self, Command},
-    time::Duration,
-};
+use std::{path, process};
 
-use console::{Emoji, style};
-use dialoguer::{Input, Select};
-use indicatif::ProgressBar;
+use clap::ArgMatches;
+use console::Term;
+use dialoguer::{FuzzySelect, Input, Select};
 
 use super::config::CliParserOptions;
+use super::fragment_cli::resolve_fragments;
 use super::theme::CliTheme;
 use crate::{
-    file_system::{copy_fs_objects, create_empty_directory},
-    templates::{config::RegisteredTemplate, get_templates},
+    generator::{GenerationReport, ProjectAnswers, ProjectGenerator},
+    projects::config::{ProjectsConfig, RegisteredProject},
+    templates::{
+        config::{RegisteredTemplate, TemplatesConfig},
+        get_templates,
+        resolver::{ResolvedTemplate, TemplateResolver},
+    },
 };
 
-pub fn run_new_project_cli_args(options: &CliParserOptions) {
-    let project_cmd = options.matches.subcommand_matches("project").unwrap();
-
-    let project_name = if let Some(name) = project_cmd.get_one::<String>("name") {
-        name
+fn gather_answers(project_cmd: &ArgMatches) -> ProjectAnswers {
+    let name = if let Some(name) = project_cmd.get_one::<String>("name") {
+        name.clone()
     } else {
-        let input: String = Input::with_theme(&CliTheme::default())
+        Input::with_theme(&CliTheme::default())
             .with_prompt("Enter the project's name (Required)")
             .with_post_completion_text("Project Name")
             .allow_empty(false)
             .interact()
-            .unwrap();
-
-        &input.to_string()
+            .unwrap()
     };
 
-    let supplied_project_path = if let Some(output) = project_cmd.get_one::<String>("output") {
-        output
+    let output = if let Some(output) = project_cmd.get_one::<String>("output") {
+        output.clone()
     } else {
-        let input: String = Input::with_theme(&CliTheme::default())
+        Input::with_theme(&CliTheme::default())
             .with_prompt("Enter the project's output path")
             .with_post_completion_text("Project Path")
             .show_default(true)
             .default("./".to_string())
             .interact()
-            .unwrap();
-
-        &input.to_string()
+            .unwrap()
     };
 
-    let project_path =
-        if PathBuf::from(supplied_project_path).ends_with(format!("/{}", project_name)) {
-            supplied_project_path.clone()
-        } else {
-            format!("{}/{}", supplied_project_path.clone(), project_name)
-        };
-
-    let registered_templates = get_templates(options.metadata.templates_meta);
-    let template_names = {
-        let mut names = Vec::new();
-        registered_templates.iter().for_each(|entry| {
-            names.push(entry.name.clone());
-        });
-
-        names
+    let optional_answer = |id: &str| {
+        project_cmd
+            .get_one::<String>(id)
+            .cloned()
+            .unwrap_or_default()
     };
 
-    if let Some(template) = project_cmd.get_one::<String>("template") {
-        let contains_template = template_names.contains(template);
-
-        let template_path = if contains_template {
-            format!("{}/{}", options.metadata.templates_meta.directory, template)
-        } else {
-            template.to_string()
-        };
-
-        if template_path == "none" {
-            let _ = create_empty_directory(&project_path);
-        } else if !contains_template || fs::metadata(&template_path).is_err() {
-            // Path does not exist and template does not exist in templates directory, so print an error and exit
-            eprintln!(
-                "The path {} does not exist! Please supply a valid folder path or an a supported template.",
-                template
-            );
-            process::exit(1);
-        } else {
-            let template_config = RegisteredTemplate::load_config(&template_path);
-
-            // Copy the files if the template already exists in the templates source folder
-            let copy_result =
-                copy_fs_objects(template_path, &project_path, &template_config.exclude_paths);
-            if copy_result.is_err() {
-                eprintln!("Something bad happened while creating the project.",);
-                process::exit(1);
-            } else {
-                let bar = ProgressBar::new_spinner().with_message(format!(
-                    "{} {}",
-                    style(Emoji("⚙️", "⚙")).blue().bright(),
-                    style("Running scripts...").bold()
-                ));
-
-                bar.enable_steady_tick(Duration::from_millis(100));
-
-                let project_scripts = template_config.scripts.clone();
-                let args = project_scripts
-                    .iter()
-                    .map(|s| s.as_str())
-                    .collect::<Vec<&str>>();
-
-                let scripts_output = if cfg!(target_os = "windows") {
-                    Command::new("cmd")
-                        .current_dir(&project_path)
-                        .args(vec![vec!["/C"], args].concat())
-                        .output()
-                        .expect("Failed to run scripts")
-                } else {
-                    Command::new("sh")
-                        .current_dir(&project_path)
-                        .args(vec![vec!["-c"], args].concat())
-                        .output()
-                        .expect("Failed to run scripts")
-                };
-
-                bar.finish_and_clear();
-
-                let _ = io::stdout().write(&scripts_output.stdout);
-                let _ = io::stderr().write(&scripts_output.stderr);
-
-                if scripts_output.status.success() {
-                    println!(
-                        "{} {}",
-                        style(Emoji("✅", "✔")).green().bright(),
-                        style("Scripts completed successfully!").green().bold()
-                    );
-                } else {
-                    println!(
-                        "{} {}",
-                        style(Emoji("❌", "𝗑")).red().bright(),
-                        style("Scripts completed with errors.").red().bold()
-                    );
-                }
+    ProjectAnswers {
+        name,
+        output,
+        description: optional_answer("description"),
+        version: optional_answer("version"),
+        author: optional_answer("author"),
+    }
+}
+
+/// Describes each template by its name, version and description, in aligned columns
+fn template_items(templates: &[RegisteredTemplate]) -> Vec<String> {
+    let details = templates
+        .iter()
+        .map(|template| {
+            // Templates without a readable config are listed without details
+            match RegisteredTemplate::read_config(&template.path) {
+                Some(config) => (config.version, config.description),
+                None => (String::new(), String::new()),
             }
-        }
+        })
+        .collect::<Vec<(String, String)>>();
+
+    let name_width = templates
+        .iter()
+        .map(|template| template.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let version_width = details
+        .iter()
+        .map(|(version, _)| version.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    templates
+        .iter()
+        .zip(details)
+        .map(|(template, (version, description))| {
+            format!(
+                "{:name_width$}  {:version_width$}  {}",
+                template.name, version, description
+            )
+            .trim_end()
+            .to_string()
+        })
+        .collect()
+}
+
+/// Asks for one of the templates, letting the user type to filter them once they don't fit on
+/// the screen
+fn select_template(templates: &[RegisteredTemplate]) -> usize {
+    let items = template_items(templates);
+    let prompt = "Select project template";
+
+    // Leaves room for the prompt and the line it's typed on
+    let (rows, _) = Term::stderr().size();
+    if items.len() + 2 > usize::from(rows) {
+        FuzzySelect::with_theme(&CliTheme::default())
+            .with_prompt(prompt)
+            .default(0)
+            .items(&items)
+            .interact()
+            .unwrap()
     } else {
-        // Show multiselect prompts
-        let templates = template_names;
-
-        if templates.len() > 0 {
-            let selection = Select::with_theme(&CliTheme::default())
-                .with_prompt("Select project template")
-                .default(0)
-                .items(&templates)
-                .interact()
-                .unwrap();
-
-            if selection == 0 {
-                let _ = create_empty_directory(&project_path);
-            } else {
-                let template_source = path::absolute(registered_templates[selection].path.clone())
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .to_string();
-
-                let template_config = RegisteredTemplate::load_config(&template_source);
-
-                // Copy the files if the template already exists in the templates source folder
-                let copy_result = copy_fs_objects(
-                    &template_source,
-                    &project_path,
-                    &template_config.exclude_paths,
-                );
-
-                if copy_result.is_err() {
-                    eprintln!("Something bad happened while creating the project.",);
-                    process::exit(1);
-                } else if template_config.scripts.len() > 0 {
-                    let bar = ProgressBar::new_spinner().with_message(format!(
-                        "{} {}",
-                        style(Emoji("⚙️", "⚙")).blue().bright(),
-                        style("Running scripts...").bold()
-                    ));
-
-                    bar.enable_steady_tick(Duration::from_millis(100));
-
-                    let project_scripts = template_config.scripts.clone();
-                    let args = project_scripts
-                        .iter()
-                        .map(|s| s.as_str())
-                        .collect::<Vec<&str>>();
-
-                    let scripts_output = if cfg!(target_os = "windows") {
-                        Command::new("cmd")
-                            .current_dir(&project_path)
-                            .args(vec![vec!["/C"], args].concat())
-                            // .stdout(Stdio::piped())
-                            // .stderr(Stdio::piped())
-                            .output()
-                            .expect("Failed to run scripts")
-                    } else {
-                        Command::new("sh")
-                            .current_dir(&project_path)
-                            .args(vec![vec!["-c"], args].concat())
-                            // .stdout(Stdio::piped())
-                            // .stderr(Stdio::piped())
-                            .output()
-                            .expect("Failed to run scripts")
-                    };
-
-                    bar.finish_and_clear();
-
-                    let _ = io::stdout().write(&scripts_output.stdout);
-                    let _ = io::stderr().write(&scripts_output.stderr);
-
-                    if scripts_output.status.success() {
-                        println!(
-                            "{} {}",
-                            style(Emoji("✅", "✔")).green().bright(),
-                            style("Scripts completed successfully!").green().bold()
-                        );
-                    } else {
-                        println!(
-                            "{} {}",
-                            style(Emoji("❌", "𝗑")).red().bright(),
-                            style("Scripts completed with errors.").red().bold()
-                        );
-                    }
-                } else {
-                    println!(
-                        "\n{} {}",
-                        style(Emoji("✅", "✔")).green().bright(),
-                        style("No scripts to run.").yellow().bold(),
-                    );
-                }
-            }
-        } else {
-            // No templates exist, so print an error and exit
-            eprintln!(
-                "No templates currently exist so supply a path for the `--template` argument."
-            );
-            process::exit(1);
-        }
+        Select::with_theme(&CliTheme::default())
+            .with_prompt(prompt)
+            .default(0)
+            .items(&items)
+            .interact()
+            .unwrap()
+    }
+}
+
+pub fn run_new_project_cli_args(options: &CliParserOptions) {
+    let project_cmd = options.matches.subcommand_matches("project").unwrap();
+
+    let answers = gather_answers(project_cmd);
+    let resolver = TemplateResolver::load(options.metadata.templates_meta);
+    let template = if project_cmd.get_one::<bool>("create_empty") == Some(&true) {
+        Ok(ResolvedTemplate::Empty)
+    } else if let Some(template) = project_cmd.get_one::<String>("template") {
+        resolver.resolve(template)
+    } else {
+        // Show select prompts
+        let registered_templates = get_templates(options.metadata.templates_meta);
+        let selection = select_template(&registered_templates);
+
+        TemplateResolver::resolve_registered(&registered_templates[selection])
+    };
+
+    let template = template.unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        process::exit(1);
+    });
+
+    let generator = ProjectGenerator {
+        template,
+        answers,
+        keep_on_failure: project_cmd.get_flag("keep_on_failure"),
+        fragments: resolve_fragments(
+            &resolver,
+            project_cmd
+                .get_many::<String>("fragments")
+                .unwrap_or_default(),
+        ),
+        resolver,
+    };
+
+    let report = generator.generate().unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        process::exit(error.exit_code());
+    });
+
+    if let ResolvedTemplate::Directory(template_path) = &generator.template
+        && let Err(error) =
+            TemplatesConfig::record_use(template_path, options.metadata.templates_meta)
+    {
+        eprintln!("Unable to record the use of the template: {}.", error);
     }
+    register_project(&generator.answers, &report, project_cmd, options);
+    ProjectGenerator::report(&report);
+}
 
-    println!(
-        "\n{} {}",
-        style(Emoji("🚀", "✔")).green().bright(),
-        style("All the best!").yellow().bold(),
-    );
+/// Records a newly created project in the projects registry
+fn register_project(
+    answers: &ProjectAnswers,
+    report: &GenerationReport,
+    project_cmd: &ArgMatches,
+    options: &CliParserOptions,
+) {
+    let (template, template_version) = match &report.template {
+        Some((name, version)) => (
+            Some(name.clone()),
+            Some(version.clone()).filter(|version| !version.is_empty()),
+        ),
+        None => (None, None),
+    };
+
+    let project_path = path::absolute(&report.project_path).unwrap();
+    let mut project = RegisteredProject {
+        description: answers.description.clone(),
+        version: answers.version.clone(),
+        template,
+        template_version,
+        author: answers.author.clone(),
+        owner: project_cmd.get_one::<String>("owner").cloned(),
+        metadata: project_cmd
+            .get_many::<(String, String)>("metadata")
+            .unwrap_or_default()
+            .cloned()
+            .collect(),
+        ..RegisteredProject::new(&answers.name, project_path.to_str().unwrap())
+    };
+    project.add_tags(project_cmd.get_many::<String>("tags").unwrap_or_default());
+
+    // The project exists either way, so failing to record it is only worth a warning
+    if let Err(error) = ProjectsConfig::register_project(&project, options.metadata.projects_meta) {
+        eprintln!(
+            "Unable to add the project to the projects registry: {}.",
+            error
+        );
+    }
 }
@@ -0,0 +1,494 @@
+use std::{
+    fs,
+    path::{self, Path, PathBuf},
+    process,
+};
+
+use clap::ArgMatches;
+use console::style;
+use dialoguer::{Confirm, Select};
+
+use super::{config::CliParserOptions, theme::CliTheme};
+use crate::projects::{
+    config::{ProjectsConfig, RegisteredProject},
+    detect::{detect_project, scan_projects},
+    filter::{ProjectFilter, ProjectSort, parse_since},
+    lifecycle::{archive_project, check_managed_path, move_project},
+    lookup::find_projects,
+};
+
+pub fn load_projects(options: &CliParserOptions) -> ProjectsConfig {
+    ProjectsConfig::load(options.metadata.projects_meta).unwrap_or_else(|error| {
+        eprintln!("Unable to read the projects registry: {}.", error);
+        process::exit(1);
+    })
+}
+
+/// Finds the project `cmd` asks for, letting the user pick one when the name is ambiguous
+pub fn select_project(config: &ProjectsConfig, cmd: &ArgMatches) -> RegisteredProject {
+    let requested = cmd.get_one::<String>("project").unwrap();
+
+    let matches = find_projects(&config.registry, requested);
+    match matches.as_slice() {
+        [] => {
+            eprintln!("No registered project is called {}.", requested);
+            process::exit(1);
+        }
+        [project] => (*project).clone(),
+        _ => {
+            let items = matches
+                .iter()
+                .map(|project| format!("{} ({})", project.name, project.path))
+                .collect::<Vec<String>>();
+
+            let selection = Select::with_theme(&CliTheme::default())
+                .with_prompt(format!("Several projects match {}", requested))
+                .default(0)
+                .items(&items)
+                .interact()
+                .unwrap();
+
+            matches[selection].clone()
+        }
+    }
+}
+
+fn save_projects(options: &CliParserOptions, config: &ProjectsConfig) {
+    if let Err(error) = config.save(options.metadata.projects_meta) {
+        eprintln!("Unable to update the projects registry: {}.", error);
+        process::exit(1);
+    }
+}
+
+/// The registry entry of a project found by `select_project`
+fn registered_mut<'a>(
+    config: &'a mut ProjectsConfig,
+    project: &RegisteredProject,
+) -> &'a mut RegisteredProject {
+    config
+        .registry
+        .iter_mut()
+        .find(|registered| registered.path == project.path)
+        .unwrap()
+}
+
+/// Checks that edna may change the project's folder, exiting when it may not
+fn managed_path(config: &ProjectsConfig, project: &RegisteredProject) -> PathBuf {
+    check_managed_path(project, &config.registry).unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        process::exit(1);
+    })
+}
+
+/// The filter set by the options of `filter_args`
+pub fn project_filter(cmd: &ArgMatches) -> ProjectFilter {
+    ProjectFilter {
+        template: cmd.get_one::<String>("template").cloned(),
+        tags: cmd
+            .get_many::<String>("tags")
+            .unwrap_or_default()
+            .cloned()
+            .collect(),
+        owner: cmd.get_one::<String>("owner").cloned(),
+        metadata: cmd
+            .get_many::<(String, String)>("metadata")
+            .unwrap_or_default()
+            .cloned()
+            .collect(),
+        ..ProjectFilter::default()
+    }
+}
+
+/// Prints projects as a table, or as JSON when asked to
+fn print_projects(projects: &[RegisteredProject], as_json: bool) {
+    if as_json {
+        println!("{}", serde_json::to_string_pretty(projects).unwrap());
+        return;
+    }
+
+    if projects.is_empty() {
+        println!("No projects found.");
+        return;
+    }
+
+    let template_of =
+        |project: &RegisteredProject| match (&project.template, &project.template_version) {
+            (Some(template), Some(version)) => format!("{}@{}", template, version),
+            (Some(template), None) => template.clone(),
+            (None, _) => String::from("-"),
+        };
+    let name_width = projects
+        .iter()
+        .map(|project| project.name.len())
+        .max()
+        .unwrap_or(0);
+    let template_width = projects
+        .iter()
+        .map(|project| template_of(project).len())
+        .max()
+        .unwrap_or(0);
+
+    for project in projects {
+        let missing = if project.archived.is_some() {
+            style(" (archived)").yellow().to_string()
+        } else if Path::new(&project.path).exists() {
+            String::new()
+        } else {
+            style(" (missing)").red().to_string()
+        };
+
+        let tags = if project.tags.is_empty() {
+            String::new()
+        } else {
+            style(format!(" [{}]", project.tags.join(", ")))
+                .dim()
+                .to_string()
+        };
+
+        println!(
+            "{:name_width$}  {:template_width$}  {}  {}{}{}",
+            style(&project.name).bold(),
+            template_of(project),
+            project.created.format("%Y-%m-%d"),
+            project.path,
+            tags,
+            missing,
+        );
+    }
+}
+
+/// Sorts and prints the registered projects that match `filter`
+fn list_projects(options: &CliParserOptions, cmd: &ArgMatches, filter: ProjectFilter) {
+    let mut projects = load_projects(options)
+        .registry
+        .into_iter()
+        .filter(|project| filter.matches(project))
+        .collect::<Vec<RegisteredProject>>();
+
+    cmd.get_one::<String>("sort")
+        .map(|sort| sort.parse::<ProjectSort>().unwrap())
+        .unwrap_or_default()
+        .sort(&mut projects);
+    if cmd.get_flag("reverse") {
+        projects.reverse();
+    }
+
+    print_projects(&projects, cmd.get_flag("json"));
+}
+
+/// Registers a project that wasn't created by edna, or every project found in a folder
+fn import_projects(options: &CliParserOptions, import_cmd: &ArgMatches) {
+    let detected = if let Some(scan_root) = import_cmd.get_one::<String>("scan") {
+        let scan_root = path::absolute(scan_root).unwrap();
+        let depth = *import_cmd.get_one::<usize>("depth").unwrap();
+        let detected = scan_projects(&scan_root, depth).unwrap_or_else(|error| {
+            eprintln!("Unable to scan {}: {}.", scan_root.display(), error);
+            process::exit(1);
+        });
+
+        if detected.is_empty() {
+            println!("No projects found in {}.", scan_root.display());
+        }
+        detected
+    } else {
+        let project_path = path::absolute(import_cmd.get_one::<String>("path").unwrap()).unwrap();
+        match detect_project(&project_path) {
+            Some(project) => vec![project],
+            None => {
+                eprintln!(
+                    "{} does not look like a project. Projects have a Cargo.toml, package.json, pyproject.toml, go.mod or .git.",
+                    project_path.display()
+                );
+                process::exit(1);
+            }
+        }
+    };
+
+    let mut config = load_projects(options);
+    for project in detected {
+        let project_path = project.path.to_str().unwrap().to_string();
+        if config
+            .registry
+            .iter()
+            .any(|registered| registered.path == project_path)
+        {
+            println!("Already registered {} at {}", project.name, project_path);
+            continue;
+        }
+
+        println!(
+            "{} {} {}",
+            style("Imported").green().bold(),
+            style(&project.name).bold(),
+            project_path
+        );
+        config.registry.push(RegisteredProject {
+            language: project.language,
+            ..RegisteredProject::new(&project.name, &project_path)
+        });
+    }
+
+    save_projects(options, &config);
+}
+
+/// Compresses a project into a `.tar.gz` archive and, unless asked to keep it, removes its folder
+fn archive_project_cli(options: &CliParserOptions, archive_cmd: &ArgMatches) {
+    let mut config = load_projects(options);
+    let project = select_project(&config, archive_cmd);
+    if let Some(archive) = &project.archived {
+        eprintln!("{} is already archived at {}.", project.name, archive);
+        process::exit(1);
+    }
+
+    let project_path = managed_path(&config, &project);
+    let archive_path = match archive_cmd.get_one::<String>("output") {
+        Some(output) => path::absolute(output).unwrap(),
+        None => {
+            let folder_name = project_path.file_name().unwrap().to_string_lossy();
+            project_path.with_file_name(format!("{}.tar.gz", folder_name))
+        }
+    };
+
+    if let Err(error) = archive_project(&project_path, &archive_path) {
+        eprintln!("Unable to archive {}: {}.", project.name, error);
+        process::exit(1);
+    }
+    if !archive_cmd.get_flag("keep")
+        && let Err(error) = fs::remove_dir_all(&project_path)
+    {
+        eprintln!(
+            "{} was archived, but its folder couldn't be removed: {}.",
+            project.name, error
+        );
+    }
+
+    let archive = archive_path.to_str().unwrap().to_string();
+    registered_mut(&mut config, &project).archived = Some(archive.clone());
+    save_projects(options, &config);
+
+    println!(
+        "{} {} to {}",
+        style("Archived").green().bold(),
+        project.name,
+        archive
+    );
+}
+
+/// Prints the tags, owner and metadata of a project
+fn print_project_details(project: &RegisteredProject) {
+    println!("{}", style(&project.name).bold());
+    println!(
+        "  tags: {}",
+        if project.tags.is_empty() {
+            String::from("-")
+        } else {
+            project.tags.join(", ")
+        }
+    );
+    println!("  owner: {}", project.owner.as_deref().unwrap_or("-"));
+    for (key, value) in &project.metadata {
+        println!("  {}: {}", key, value);
+    }
+}
+
+/// Changes the tags, owner and metadata of a project, or prints them when nothing is changed
+fn tag_project_cli(options: &CliParserOptions, tag_cmd: &ArgMatches) {
+    let mut config = load_projects(options);
+    let project = select_project(&config, tag_cmd);
+    let registered = registered_mut(&mut config, &project);
+
+    let tags = tag_cmd.get_many::<String>("tags").unwrap_or_default();
+    if tag_cmd.get_flag("remove") {
+        let removed = tags.collect::<Vec<&String>>();
+        registered.tags.retain(|tag| !removed.contains(&tag));
+    } else {
+        registered.add_tags(tags);
+    }
+    if let Some(owner) = tag_cmd.get_one::<String>("owner") {
+        registered.owner = Some(owner.clone()).filter(|owner| !owner.is_empty());
+    }
+    registered.metadata.extend(
+        tag_cmd
+            .get_many::<(String, String)>("metadata")
+            .unwrap_or_default()
+            .cloned(),
+    );
+    for key in tag_cmd.get_many::<String>("unset").unwrap_or_default() {
+        registered.metadata.remove(key);
+    }
+
+    let updated = registered.clone();
+    save_projects(options, &config);
+    print_project_details(&updated);
+}
+
+fn rename_project_cli(options: &CliParserOptions, rename_cmd: &ArgMatches) {
+    let mut config = load_projects(options);
+    let project = select_project(&config, rename_cmd);
+    let new_name = rename_cmd.get_one::<String>("new_name").unwrap();
+
+    if config
+        .registry
+        .iter()
+        .any(|registered| &registered.name == new_name)
+    {
+        eprintln!("A project called {} is already registered.", new_name);
+        process::exit(1);
+    }
+
+    registered_mut(&mut config, &project).name = new_name.clone();
+    save_projects(options, &config);
+
+    println!(
+        "{} {} to {}",
+        style("Renamed").green().bold(),
+        project.name,
+        new_name
+    );
+}
+
+/// Moves a project's folder, into the destination when that's an existing folder
+fn move_project_cli(options: &CliParserOptions, move_cmd: &ArgMatches) {
+    let mut config = load_projects(options);
+    let project = select_project(&config, move_cmd);
+    let project_path = managed_path(&config, &project);
+
+    // Collecting the components drops any trailing separator
+    let mut destination = path::absolute(move_cmd.get_one::<String>("destination").unwrap())
+        .unwrap()
+        .components()
+        .collect::<PathBuf>();
+    if destination.is_dir() {
+        destination = destination.join(project_path.file_name().unwrap());
+    }
+    if destination.starts_with(&project_path) {
+        eprintln!("A project can't be moved into its own folder.");
+        process::exit(1);
+    }
+
+    if let Err(error) = move_project(&project_path, &destination) {
+        eprintln!("Unable to move {}: {}.", project.name, error);
+        process::exit(1);
+    }
+
+    let destination = destination.to_str().unwrap().to_string();
+    registered_mut(&mut config, &project).path = destination.clone();
+    save_projects(options, &config);
+
+    println!(
+        "{} {} to {}",
+        style("Moved").green().bold(),
+        project.name,
+        destination
+    );
+}
+
+/// Removes a project's folder, after asking, and takes it out of the registry
+fn delete_project_cli(options: &CliParserOptions, delete_cmd: &ArgMatches) {
+    let mut config = load_projects(options);
+    let project = select_project(&config, delete_cmd);
+    let keep_files = delete_cmd.get_flag("keep_files");
+    // Archived projects usually have no folder left, and missing ones are only unregistered
+    let project_path =
+        (!keep_files && Path::new(&project.path).exists()).then(|| managed_path(&config, &project));
+    let archive_path = project
+        .archived
+        .as_ref()
+        .map(PathBuf::from)
+        .filter(|archive| !keep_files && archive.is_file());
+    let mut delete_archive = archive_path.is_some();
+
+    if !delete_cmd.get_flag("yes") {
+        let prompt = match &project_path {
+            Some(project_path) => format!(
+                "Delete {} and everything in {}?",
+                project.name,
+                project_path.display()
+            ),
+            None => format!("Remove {} from the registry?", project.name),
+        };
+        let confirmed = Confirm::with_theme(&CliTheme::default())
+            .with_prompt(prompt)
+            .default(false)
+            .interact()
+            .unwrap();
+
+        if !confirmed {
+            println!("Nothing was deleted.");
+            return;
+        }
+
+        if let Some(archive_path) = &archive_path {
+            delete_archive = Confirm::with_theme(&CliTheme::default())
+                .with_prompt(format!(
+                    "Also delete its archive {}?",
+                    archive_path.display()
+                ))
+                .default(false)
+                .interact()
+                .unwrap();
+        }
+    }
+
+    if let Some(project_path) = &project_path
+        && let Err(error) = fs::remove_dir_all(project_path)
+    {
+        eprintln!("Unable to delete {}: {}.", project.name, error);
+        process::exit(1);
+    }
+    if let Some(archive_path) = &archive_path
+        && delete_archive
+        && let Err(error) = fs::remove_file(archive_path)
+    {
+        eprintln!(
+            "Unable to delete the archive of {}: {}.",
+            project.name, error
+        );
+        process::exit(1);
+    }
+
+    config
+        .registry
+        .retain(|registered| registered.path != project.path);
+    save_projects(options, &config);
+
+    println!("{} {}", style("Deleted").green().bold(), project.name);
+}
+
+pub fn run_projects_cli_args(options: &CliParserOptions) {
+    let projects_cmd = options.matches.subcommand_matches("projects").unwrap();
+
+    if let Some(list_cmd) = projects_cmd.subcommand_matches("list") {
+        let since = list_cmd.get_one::<String>("since").map(|since| {
+            parse_since(since).unwrap_or_else(|error| {
+                eprintln!("{}", error);
+                process::exit(1);
+            })
+        });
+
+        let filter = ProjectFilter {
+            since,
+            missing: list_cmd.get_flag("missing"),
+            ..project_filter(list_cmd)
+        };
+        list_projects(options, list_cmd, filter);
+    } else if let Some(search_cmd) = projects_cmd.subcommand_matches("search") {
+        let filter = ProjectFilter {
+            text: search_cmd.get_one::<String>("text").cloned(),
+            ..ProjectFilter::default()
+        };
+        list_projects(options, search_cmd, filter);
+    } else if let Some(import_cmd) = projects_cmd.subcommand_matches("import") {
+        import_projects(options, import_cmd);
+    } else if let Some(archive_cmd) = projects_cmd.subcommand_matches("archive") {
+        archive_project_cli(options, archive_cmd);
+    } else if let Some(tag_cmd) = projects_cmd.subcommand_matches("tag") {
+        tag_project_cli(options, tag_cmd);
+    } else if let Some(rename_cmd) = projects_cmd.subcommand_matches("rename") {
+        rename_project_cli(options, rename_cmd);
+    } else if let Some(move_cmd) = projects_cmd.subcommand_matches("move") {
+        move_project_cli(options, move_cmd);
+    } else if let Some(delete_cmd) = projects_cmd.subcommand_matches("delete") {
+        delete_project_cli(options, delete_cmd);
+    }
+}
@@ -0,0 +1,93 @@
+use std::{env, path::Path, process};
+
+use console::style;
+
+use super::{config::CliParserOptions, projects_cli::load_projects};
+use crate::projects::{
+    config::RegisteredProject,
+    tasks::{ProjectTasks, find_project_tasks},
+};
+
+/// The variables the task can read, taken from the projects registry when the project is in it
+fn task_environment(options: &CliParserOptions, root: &Path) -> Vec<(&'static str, String)> {
+    let registered = load_projects(options)
+        .registry
+        .into_iter()
+        .find(|project| Path::new(&project.path) == root);
+
+    registered
+        .unwrap_or_else(|| {
+            RegisteredProject::new(
+                &root
+                    .file_name()
+                    .map(|name| name.to_string_lossy())
+                    .unwrap_or_default(),
+                &root.to_string_lossy(),
+            )
+        })
+        .environment()
+}
+
+fn print_tasks(project_tasks: &ProjectTasks) {
+    if project_tasks.tasks.is_empty() {
+        println!("The project has no tasks.");
+        return;
+    }
+
+    let name_width = project_tasks
+        .tasks
+        .keys()
+        .map(|name| name.len())
+        .max()
+        .unwrap_or(0);
+    for (name, task) in &project_tasks.tasks {
+        println!(
+            "{}  {}",
+            style(format!("{:name_width$}", name)).bold(),
+            task.label()
+        );
+    }
+}
+
+pub fn run_task_cli_args(options: &CliParserOptions) {
+    let run_cmd = options.matches.subcommand_matches("run").unwrap();
+
+    let current_dir = env::current_dir().unwrap();
+    let (root, project_tasks) = match find_project_tasks(&current_dir) {
+        Ok(Some(found)) => found,
+        Ok(None) => {
+            eprintln!(
+                "No edna.config.json was found in {} or the folders above it.",
+                current_dir.display()
+            );
+            process::exit(1);
+        }
+        Err(error) => {
+            eprintln!("Unable to read the project's tasks: {}.", error);
+            process::exit(1);
+        }
+    };
+
+    let Some(name) = run_cmd.get_one::<String>("task") else {
+        print_tasks(&project_tasks);
+        return;
+    };
+    let Some(task) = project_tasks.tasks.get(name) else {
+        eprintln!("The project has no task called {}.", name);
+        print_tasks(&project_tasks);
+        process::exit(1);
+    };
+
+    // Tasks like `dev` may be long-running and interactive, so they keep the terminal
+    let status = task
+        .command(project_tasks.shell.as_deref(), &root.to_string_lossy())
+        .current_dir(&root)
+        .envs(task_environment(options, &root))
+        .status()
+        .unwrap_or_else(|error| {
+            eprintln!("Unable to run {}: {}.", name, error);
+            process::exit(1);
+        });
+
+    process::exit(status.code().unwrap_or(1));
+}
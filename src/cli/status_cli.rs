@@ -0,0 +1,172 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use console::style;
+use indicatif::HumanBytes;
+
+use super::{config::CliParserOptions, projects_cli::load_projects};
+use crate::{
+    projects::status::{ProjectStatus, project_statuses},
+    templates::{
+        config::{RegisteredTemplate, TemplatesConfig},
+        resolver::{ResolvedTemplate, TemplateResolver},
+    },
+};
+
+/// The current version of each template the projects were created from, skipping templates that
+/// can no longer be found or read. Every version is unknown when the templates registry can't be
+/// read, e.g. outside the folder holding it.
+fn latest_template_versions(
+    options: &CliParserOptions,
+    templates: impl Iterator<Item = String>,
+) -> BTreeMap<String, String> {
+    let Some(config) = TemplatesConfig::read(options.metadata.templates_meta) else {
+        return BTreeMap::new();
+    };
+
+    let resolver = TemplateResolver::new(config.registry);
+    templates
+        .filter_map(|template| match resolver.resolve(&template) {
+            Ok(ResolvedTemplate::Directory(path)) => {
+                let version = RegisteredTemplate::read_config(&path)?.version;
+                Some((template, version)).filter(|(_, version)| !version.is_empty())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// The columns of a project's row in the status table
+struct StatusRow<'a> {
+    status: &'a ProjectStatus,
+    branch: String,
+    dirty: bool,
+    last_commit: String,
+    template: String,
+    size: String,
+}
+
+fn print_statuses(statuses: &[ProjectStatus]) {
+    if statuses.is_empty() {
+        println!("No projects found.");
+        return;
+    }
+
+    let rows = statuses
+        .iter()
+        .map(|status| {
+            let (branch, dirty, last_commit) = match &status.git {
+                Some(git) => (
+                    git.branch.clone().unwrap_or_else(|| String::from("-")),
+                    git.dirty,
+                    git.last_commit
+                        .map(|date| date.format("%Y-%m-%d").to_string())
+                        .unwrap_or_else(|| String::from("-")),
+                ),
+                None => (String::from("-"), false, String::from("-")),
+            };
+            let template = match (&status.template, &status.template_version) {
+                (Some(template), Some(version)) => format!("{}@{}", template, version),
+                (Some(template), None) => template.clone(),
+                (None, _) => String::from("-"),
+            };
+            let size = status
+                .disk_usage
+                .map(|size| HumanBytes(size).to_string())
+                .unwrap_or_else(|| String::from("-"));
+
+            StatusRow {
+                status,
+                branch,
+                dirty,
+                last_commit,
+                template,
+                size,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let width = |column: fn(&StatusRow) -> usize| rows.iter().map(column).max().unwrap_or(0);
+    let name_width = width(|row| row.status.name.len()).max(4);
+    let branch_width = width(|row| row.branch.len()).max(6);
+    let template_width = width(|row| row.template.len()).max(8);
+    let size_width = width(|row| row.size.len()).max(4);
+
+    println!(
+        "{}",
+        style(format!(
+            "{:name_width$}  {:branch_width$}  {:5}  {:11}  {:template_width$}  {:>size_width$}",
+            "NAME", "BRANCH", "STATE", "LAST COMMIT", "TEMPLATE", "SIZE"
+        ))
+        .dim()
+    );
+
+    for StatusRow {
+        status,
+        branch,
+        dirty,
+        last_commit,
+        template,
+        size,
+    } in &rows
+    {
+        let state = if status.archived {
+            style(format!("{:5}", "arch")).yellow()
+        } else if !status.exists {
+            style(format!("{:5}", "gone")).red()
+        } else if *dirty {
+            style(format!("{:5}", "dirty")).yellow()
+        } else if status.git.is_some() {
+            style(format!("{:5}", "clean")).green()
+        } else {
+            style(format!("{:5}", "-"))
+        };
+        let template = if status.outdated {
+            style(format!("{:template_width$}", template)).yellow()
+        } else {
+            style(format!("{:template_width$}", template))
+        };
+        let behind = match (&status.latest_template_version, status.outdated) {
+            (Some(latest), true) => style(format!("  {} available", latest))
+                .yellow()
+                .to_string(),
+            _ => String::new(),
+        };
+
+        println!(
+            "{}  {:branch_width$}  {}  {:11}  {}  {:>size_width$}{}",
+            style(format!("{:name_width$}", status.name)).bold(),
+            branch,
+            state,
+            last_commit,
+            template,
+            size,
+            behind,
+        );
+    }
+}
+
+pub fn run_status_cli_args(options: &CliParserOptions) {
+    let status_cmd = options.matches.subcommand_matches("status").unwrap();
+    let projects = load_projects(options).registry;
+
+    let latest_versions = latest_template_versions(
+        options,
+        projects
+            .iter()
+            .filter_map(|project| project.template.clone())
+            .collect::<BTreeSet<String>>()
+            .into_iter(),
+    );
+    let statuses = project_statuses(&projects, |project| {
+        project
+            .template
+            .as_ref()
+            .and_then(|template| latest_versions.get(template).cloned())
+    });
+
+    if status_cmd.get_flag("json") {
+        println!("{}", serde_json::to_string_pretty(&statuses).unwrap());
+    } else {
+        print_statuses(&statuses);
+    }
+}
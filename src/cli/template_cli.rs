@@ -1,13 +1,47 @@
-use std::path;
+use std::{
+    collections::BTreeMap,
+    path::{self, PathBuf},
+    process,
+};
 
 use console::{Emoji, style};
 use dialoguer::{Input, Select};
 use whoami;
 
-use crate::templates::config::CreatedTemplateConfig;
+use crate::{
+    file_system::{content::BomPolicy, copy::SymlinkPolicy},
+    templates::config::{CreatedTemplateConfig, FRAGMENT_TARGET, PROJECT_TARGET, TemplateScript},
+};
 
 use super::{config::CliParserOptions, theme::CliTheme};
 
+const MAX_LISTED_SKIPPED_PATHS: usize = 10;
+
+fn print_skipped_paths(title: &str, paths: &[PathBuf]) {
+    if paths.is_empty() {
+        return;
+    }
+
+    let listed = paths
+        .iter()
+        .take(MAX_LISTED_SKIPPED_PATHS)
+        .map(|path| path.display().to_string())
+        .collect::<Vec<String>>();
+    let remaining = paths.len() - listed.len();
+
+    println!(
+        "\n{} {} {}{}",
+        style(Emoji("🙈", "-")).yellow().bright(),
+        style(format!("{} {} paths:", title, paths.len())).bold(),
+        listed.join(", "),
+        if remaining > 0 {
+            format!(" and {} more", remaining)
+        } else {
+            String::new()
+        }
+    );
+}
+
 pub fn run_new_template_cli_args(options: &CliParserOptions) {
     let template_cmd = options.matches.subcommand_matches("template").unwrap();
     let templates_directory = &options.metadata.templates_meta.directory;
@@ -33,7 +67,7 @@ pub fn run_new_template_cli_args(options: &CliParserOptions) {
             .with_post_completion_text("Template Author")
             .allow_empty(true)
             .show_default(true)
-            .default(String::from(whoami::realname()))
+            .default(whoami::realname())
             .interact()
             .unwrap();
 
@@ -71,12 +105,12 @@ pub fn run_new_template_cli_args(options: &CliParserOptions) {
 
     let template_exclude_config: bool =
         if let Some(exclude_config) = template_cmd.get_one::<bool>("exclude_config") {
-            exclude_config.clone()
+            *exclude_config
         } else {
             let input: usize = Select::with_theme(&CliTheme::default())
                 .with_prompt("Ignore the template's config when creating project")
                 .default(0)
-                .items(&vec![String::from("Yes"), String::from("No")])
+                .items(&[String::from("Yes"), String::from("No")])
                 .interact()
                 .unwrap();
 
@@ -85,27 +119,27 @@ pub fn run_new_template_cli_args(options: &CliParserOptions) {
 
     let template_excluded_paths: Vec<String> =
         if let Some(excluded_paths) = template_cmd.get_many::<String>("exclude_paths") {
-            excluded_paths
-                .map(|p| String::from(p))
-                .collect::<Vec<String>>()
+            excluded_paths.map(String::from).collect::<Vec<String>>()
         } else {
             let input: String = Input::with_theme(&CliTheme::default())
-                .with_prompt("Ignore certain paths when copying the template")
+                .with_prompt("Ignore paths matching gitignore-style patterns, separated by a comma")
                 .with_post_completion_text("Excluded Template Paths")
                 .allow_empty(true)
                 .interact()
                 .unwrap();
 
-            if input == "" {
+            if input.is_empty() {
                 vec![]
             } else {
-                input.split(",").map(|i| String::from(i)).collect()
+                input.split(",").map(String::from).collect()
             }
         };
 
-    let template_scripts: Vec<String> =
+    let template_scripts: Vec<TemplateScript> =
         if let Some(scripts) = template_cmd.get_many::<String>("scripts") {
-            scripts.map(|p| String::from(p)).collect::<Vec<String>>()
+            scripts
+                .map(|s| TemplateScript::Command(s.to_string()))
+                .collect::<Vec<TemplateScript>>()
         } else {
             let input: String = Input::with_theme(&CliTheme::default())
                 .with_prompt("Add initialisation scripts for the project separated by a comma")
@@ -114,13 +148,21 @@ pub fn run_new_template_cli_args(options: &CliParserOptions) {
                 .interact()
                 .unwrap();
 
-            if input == "" {
+            if input.is_empty() {
                 vec![]
             } else {
-                input.split(",").map(|i| String::from(i)).collect()
+                input
+                    .split(",")
+                    .map(|i| TemplateScript::Command(i.to_string()))
+                    .collect()
             }
         };
 
+    let template_aliases: Vec<String> = template_cmd
+        .get_many::<String>("aliases")
+        .map(|aliases| aliases.map(String::from).collect())
+        .unwrap_or_default();
+
     let template_output = if let Some(output) = template_cmd.get_one::<String>("output") {
         &path::absolute(format!("{}/{}", templates_directory, output))
             .unwrap()
@@ -128,7 +170,7 @@ pub fn run_new_template_cli_args(options: &CliParserOptions) {
             .unwrap()
             .to_string()
     } else {
-        let default_path = String::from(format!("{}/{}", templates_directory, template_name));
+        let default_path = format!("{}/{}", templates_directory, template_name);
 
         let input: String = Input::with_theme(&CliTheme::default())
             .with_prompt("Where in the templates directory to create it")
@@ -150,7 +192,7 @@ pub fn run_new_template_cli_args(options: &CliParserOptions) {
     };
 
     let template_source = if let Some(source) = template_cmd.get_one::<String>("source") {
-        if source == "" {
+        if source.is_empty() {
             &source.to_string()
         } else {
             &path::absolute(source)
@@ -167,7 +209,7 @@ pub fn run_new_template_cli_args(options: &CliParserOptions) {
             .interact()
             .unwrap();
 
-        if input == "" {
+        if input.is_empty() {
             &input.to_string()
         } else {
             &path::absolute(input).unwrap().to_str().unwrap().to_string()
@@ -176,22 +218,57 @@ pub fn run_new_template_cli_args(options: &CliParserOptions) {
 
     let template_config = CreatedTemplateConfig {
         name: template_name.clone(),
-        target: String::from("project"),
+        target: String::from(if template_cmd.get_flag("fragment") {
+            FRAGMENT_TARGET
+        } else {
+            PROJECT_TARGET
+        }),
+        extends: template_cmd.get_one::<String>("extends").cloned(),
         author: template_author.clone(),
         version: template_version.clone(),
         description: template_description.clone(),
         exclude_config: template_exclude_config,
         exclude_paths: template_excluded_paths,
         scripts: template_scripts,
+        timeout: None,
+        rollback_on_cancel: false,
+        shell: None,
+        aliases: template_aliases,
+        symlinks: template_cmd
+            .get_one::<String>("symlinks")
+            .map(|policy| policy.parse::<SymlinkPolicy>().unwrap())
+            .unwrap_or_default(),
+        preserve_timestamps: template_cmd.get_flag("preserve_timestamps"),
+        variables: BTreeMap::new(),
+        render: vec![],
+        raw: vec![],
+        line_endings: None,
+        bom: BomPolicy::Keep,
+        text_overrides: vec![],
+        components: vec![],
+        patches: vec![],
+        tasks: Default::default(),
     };
 
-    let _ = CreatedTemplateConfig::create_template(
-        &template_output,
-        &template_source,
+    let creation_result = CreatedTemplateConfig::create_template(
+        template_output,
+        template_source,
         &template_config,
-        &options.metadata.templates_meta,
+        options.metadata.templates_meta,
+        !template_cmd.get_flag("no_gitignore"),
     );
 
+    let summary = creation_result.unwrap_or_else(|error| {
+        eprintln!(
+            "Something bad happened while creating the template: {}.",
+            error
+        );
+        process::exit(1);
+    });
+
+    print_skipped_paths("Skipped", &summary.skipped);
+    print_skipped_paths("Skipped symlink loops at", &summary.loops);
+
     println!(
         "\n{} {}",
         style(Emoji("🚀", ":-)")).green().bright(),
@@ -2,6 +2,7 @@ use std::fmt;
 
 use console::{Emoji, Style, StyledObject, style};
 use dialoguer::theme::Theme;
+use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
 
 pub struct CliTheme {
     /// The style for default values
@@ -40,6 +41,10 @@ pub struct CliTheme {
     pub picked_item_prefix: StyledObject<String>,
     /// Unpicked item in sort prefix value and style
     pub unpicked_item_prefix: StyledObject<String>,
+    /// The style for the cursor of a fuzzy select prompt
+    pub fuzzy_cursor_style: Style,
+    /// The style for the characters matching the search of a fuzzy select prompt
+    pub fuzzy_match_highlight_style: Style,
 }
 
 impl Default for CliTheme {
@@ -66,6 +71,8 @@ impl Default for CliTheme {
             unchecked_item_prefix: style(Emoji("📦", "◯").to_string()).for_stderr().magenta(),
             picked_item_prefix: style("❯".to_string()).for_stderr().green(),
             unpicked_item_prefix: style(" ".to_string()).for_stderr(),
+            fuzzy_cursor_style: Style::new().for_stderr().black().on_white(),
+            fuzzy_match_highlight_style: Style::new().for_stderr().bold().underlined(),
         }
     }
 }
@@ -219,7 +226,6 @@ impl Theme for CliTheme {
     }
 
     /// Formats a password prompt after selection.
-    #[cfg(feature = "password")]
     fn format_password_prompt_selection(
         &self,
         f: &mut dyn fmt::Write,
@@ -335,4 +341,74 @@ impl Theme for CliTheme {
 
         write!(f, "{} {}", details.0, details.1)
     }
+
+    /// Formats a fuzzy select prompt item, highlighting the characters matching the search.
+    fn format_fuzzy_select_prompt_item(
+        &self,
+        f: &mut dyn fmt::Write,
+        text: &str,
+        active: bool,
+        highlight_matches: bool,
+        matcher: &SkimMatcherV2,
+        search_term: &str,
+    ) -> fmt::Result {
+        let (prefix, item_style) = if active {
+            (&self.active_item_prefix, &self.active_item_style)
+        } else {
+            (&self.inactive_item_prefix, &self.inactive_item_style)
+        };
+        write!(f, "{} ", prefix)?;
+
+        let matched = highlight_matches
+            .then(|| matcher.fuzzy_indices(text, search_term))
+            .flatten()
+            .map(|(_score, indices)| indices)
+            .unwrap_or_default();
+        for (index, character) in text.chars().enumerate() {
+            if matched.contains(&index) {
+                write!(
+                    f,
+                    "{}",
+                    item_style.apply_to(self.fuzzy_match_highlight_style.apply_to(character))
+                )?;
+            } else {
+                write!(f, "{}", item_style.apply_to(character))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Formats a fuzzy select prompt along with the search typed so far.
+    fn format_fuzzy_select_prompt(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        search_term: &str,
+        bytes_pos: usize,
+    ) -> fmt::Result {
+        if !prompt.is_empty() {
+            write!(
+                f,
+                "{} {} ",
+                &self.prompt_prefix,
+                self.prompt_style.apply_to(prompt)
+            )?;
+        }
+
+        let (head, remaining) = search_term.split_at(bytes_pos);
+        let mut characters = remaining.chars();
+        let cursor = self
+            .fuzzy_cursor_style
+            .apply_to(characters.next().unwrap_or(' '));
+
+        write!(
+            f,
+            "{} {}{}{}",
+            &self.prompt_suffix,
+            head,
+            cursor,
+            characters.as_str()
+        )
+    }
 }
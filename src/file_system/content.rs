@@ -0,0 +1,143 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// How many leading bytes are checked for null bytes, the same amount git looks at
+const SNIFF_LENGTH: usize = 8000;
+
+/// Extensions of files that are never treated as text, whatever their contents look like
+const BINARY_EXTENSIONS: [&str; 38] = [
+    "png", "jpg", "jpeg", "gif", "bmp", "ico", "icns", "webp", "tiff", "psd", "woff", "woff2",
+    "ttf", "otf", "eot", "jar", "war", "class", "zip", "gz", "tgz", "bz2", "xz", "7z", "rar",
+    "tar", "pdf", "exe", "dll", "so", "dylib", "a", "lib", "o", "wasm", "mp3", "mp4", "sqlite",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentKind {
+    Text,
+    Binary,
+}
+
+pub fn has_binary_extension<At: AsRef<Path>>(path: At) -> bool {
+    path.as_ref()
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| BINARY_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+/// Classifies contents as text when they have no null bytes near the start and are valid UTF-8
+pub fn sniff_content(bytes: &[u8]) -> ContentKind {
+    let sniffed = &bytes[..bytes.len().min(SNIFF_LENGTH)];
+    if sniffed.contains(&0) || std::str::from_utf8(bytes).is_err() {
+        ContentKind::Binary
+    } else {
+        ContentKind::Text
+    }
+}
+
+/// The byte order mark some editors put at the start of UTF-8 files
+const UTF8_BOM: char = '\u{feff}';
+
+/// The line endings text files are converted to
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEndings {
+    Lf,
+    Crlf,
+    /// Whatever the platform the project is generated on uses
+    Native,
+}
+
+impl LineEndings {
+    fn sequence(self) -> &'static str {
+        match self {
+            LineEndings::Lf => "\n",
+            LineEndings::Crlf => "\r\n",
+            LineEndings::Native if cfg!(windows) => "\r\n",
+            LineEndings::Native => "\n",
+        }
+    }
+}
+
+/// What happens to a UTF-8 byte order mark at the start of text files
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BomPolicy {
+    /// Leaves the mark in the files that have one
+    #[default]
+    Keep,
+    Strip,
+    Add,
+}
+
+/// How the line endings and byte order mark of text files are normalized
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TextFormat {
+    /// Leaves line endings as they are when unset
+    pub line_endings: Option<LineEndings>,
+    pub bom: BomPolicy,
+}
+
+/// A text format for the files matching gitignore-style patterns, taking precedence over the
+/// template's own
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TextFormatOverride {
+    pub files: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line_endings: Option<LineEndings>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bom: Option<BomPolicy>,
+}
+
+impl TextFormat {
+    /// Whether text is written back exactly as it was read
+    pub fn is_unchanged(&self) -> bool {
+        self.line_endings.is_none() && self.bom == BomPolicy::Keep
+    }
+
+    pub fn with_override(self, format: &TextFormatOverride) -> TextFormat {
+        TextFormat {
+            line_endings: format.line_endings.or(self.line_endings),
+            bom: format.bom.unwrap_or(self.bom),
+        }
+    }
+
+    /// Converts the line endings of `text`, which has had its byte order mark removed, and gives
+    /// it one when the policy asks for it
+    pub fn apply(&self, text: &str, had_bom: bool) -> String {
+        let mut formatted = String::with_capacity(text.len() + UTF8_BOM.len_utf8());
+        let add_bom = match self.bom {
+            BomPolicy::Keep => had_bom,
+            BomPolicy::Strip => false,
+            BomPolicy::Add => true,
+        };
+        if add_bom {
+            formatted.push(UTF8_BOM);
+        }
+
+        let Some(line_endings) = self.line_endings else {
+            formatted.push_str(text);
+            return formatted;
+        };
+
+        for line in text.split_inclusive('\n') {
+            match line.strip_suffix('\n') {
+                Some(line) => {
+                    formatted.push_str(line.strip_suffix('\r').unwrap_or(line));
+                    formatted.push_str(line_endings.sequence());
+                }
+                None => formatted.push_str(line),
+            }
+        }
+
+        formatted
+    }
+}
+
+/// Splits the byte order mark off the start of `text`, returning the rest and whether it had one
+pub fn strip_bom(text: &str) -> (&str, bool) {
+    match text.strip_prefix(UTF8_BOM) {
+        Some(rest) => (rest, true),
+        None => (text, false),
+    }
+}
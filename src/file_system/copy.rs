@@ -0,0 +1,832 @@
+use std::{
+    fs, io,
+    path::{self, Path, PathBuf},
+    process,
+    str::FromStr,
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::{Deserialize, Serialize};
+
+use super::content::{
+    ContentKind, TextFormat, TextFormatOverride, has_binary_extension, sniff_content, strip_bom,
+};
+use crate::{
+    scripts,
+    workers::{available_workers, for_each_parallel},
+};
+
+/// The most threads used to copy files at once
+const MAX_COPY_THREADS: usize = 8;
+
+/// The file a template can use to list extra gitignore-style patterns to leave out of copies
+pub const IGNORE_FILENAME: &str = ".ednaignore";
+
+fn add_patterns(
+    builder: &mut GitignoreBuilder,
+    root: &Path,
+    patterns: &[String],
+) -> io::Result<()> {
+    let absolute_root = path::absolute(root)?;
+    for pattern in patterns {
+        // Older configs list absolute paths, which are anchored to the root when they're inside it
+        let anchored = match Path::new(pattern).strip_prefix(&absolute_root) {
+            Ok(relative) => format!("/{}", relative.to_string_lossy().replace('\\', "/")),
+            Err(_) => pattern.clone(),
+        };
+
+        builder
+            .add_line(None, &anchored)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+    }
+
+    Ok(())
+}
+
+/// Builds a matcher for gitignore-style `patterns` relative to `root`.
+///
+/// Absolute paths inside `root` are accepted as well and are anchored to the root.
+pub fn build_pattern_matcher<At: AsRef<Path>>(
+    root: At,
+    patterns: &[String],
+) -> io::Result<Gitignore> {
+    let mut builder = GitignoreBuilder::new(root.as_ref());
+    add_patterns(&mut builder, root.as_ref(), patterns)?;
+
+    builder
+        .build()
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))
+}
+
+/// Builds a matcher like `build_pattern_matcher`, along with the patterns in the root's
+/// `.ednaignore` file when there is one
+pub fn build_exclude_matcher<At: AsRef<Path>>(
+    root: At,
+    patterns: &[String],
+) -> io::Result<Gitignore> {
+    let root = root.as_ref();
+    let mut builder = GitignoreBuilder::new(root);
+
+    let ignore_file = root.join(IGNORE_FILENAME);
+    if ignore_file.is_file()
+        && let Some(error) = builder.add(&ignore_file)
+    {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, error));
+    }
+
+    add_patterns(&mut builder, root, patterns)?;
+    add_patterns(&mut builder, root, &[format!("/{}", IGNORE_FILENAME)])?;
+
+    builder
+        .build()
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))
+}
+
+/// The ignore files honoured when copying with `CopyOptions::respect_gitignore`
+const GITIGNORE_FILENAMES: [&str; 2] = [".gitignore", ".ignore"];
+
+/// How symbolic links in the source are copied
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SymlinkPolicy {
+    /// Recreates the link itself, pointing at the same target
+    #[default]
+    Preserve,
+    /// Copies whatever the link points at
+    Follow,
+    /// Leaves links out of the copy
+    Skip,
+}
+
+impl FromStr for SymlinkPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "preserve" => Ok(SymlinkPolicy::Preserve),
+            "follow" => Ok(SymlinkPolicy::Follow),
+            "skip" => Ok(SymlinkPolicy::Skip),
+            _ => Err(format!("{} is not a symlink policy", value)),
+        }
+    }
+}
+
+/// Rewrites the contents of a text file while it's copied
+pub type TextTransform<'a> = &'a (dyn Fn(&str) -> String + Sync);
+
+#[derive(Default, Clone)]
+pub struct CopyOptions<'a> {
+    /// Gitignore-style patterns, relative to the source, that are left out of the copy
+    pub exclude: &'a [String],
+    /// Also leaves out whatever the source's `.gitignore` and `.ignore` files ignore
+    pub respect_gitignore: bool,
+    /// Copies the source's `.ednaignore` and the files it lists too, e.g. when a project is moved
+    /// rather than used as a template
+    pub disable_ednaignore: bool,
+    pub symlinks: SymlinkPolicy,
+    /// Gives copied files and folders the same modification time as their source
+    pub preserve_timestamps: bool,
+    /// Shows a progress bar with the number of files and bytes copied
+    pub show_progress: bool,
+    /// Applied to text files, e.g. to fill in template placeholders
+    pub transform_text: Option<TextTransform<'a>>,
+    /// Gitignore-style patterns of the only files `transform_text` applies to. When empty, any
+    /// file that looks like text is transformed.
+    pub render: &'a [String],
+    /// Gitignore-style patterns of files that are always copied as they are
+    pub raw: &'a [String],
+    /// The line endings and byte order mark given to text files
+    pub text_format: TextFormat,
+    /// Text formats for some of the files, the last matching one winning
+    pub text_overrides: &'a [TextFormatOverride],
+}
+
+#[derive(Debug, Default)]
+pub struct CopySummary {
+    /// The files and folders that were left out, relative to the source
+    pub skipped: Vec<PathBuf>,
+    /// Followed links that lead back into a folder already being copied, relative to the source
+    pub loops: Vec<PathBuf>,
+    /// The number of files and links copied
+    pub files: usize,
+    pub bytes: u64,
+}
+
+fn build_gitignore_matcher<At: AsRef<Path>>(directory: At) -> io::Result<Option<Gitignore>> {
+    let directory = directory.as_ref();
+    let mut builder = GitignoreBuilder::new(directory);
+    let mut has_ignore_files = false;
+
+    for filename in GITIGNORE_FILENAMES {
+        let ignore_file = directory.join(filename);
+        if ignore_file.is_file() {
+            has_ignore_files = true;
+            if let Some(error) = builder.add(&ignore_file) {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, error));
+            }
+        }
+    }
+
+    if !has_ignore_files {
+        return Ok(None);
+    }
+
+    builder
+        .build()
+        .map(Some)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))
+}
+
+/// How a file is rewritten if its contents turn out to be text
+#[derive(Debug, Clone, Copy)]
+struct TextCopy {
+    /// Whether `CopyOptions::transform_text` applies to the file
+    render: bool,
+    format: TextFormat,
+}
+
+/// A file or link found while walking the source, copied once the walk is done
+enum CopyTask {
+    File {
+        from: PathBuf,
+        to: PathBuf,
+        /// Unset for files that are copied as they are
+        text: Option<TextCopy>,
+    },
+    Symlink {
+        from: PathBuf,
+        to: PathBuf,
+    },
+}
+
+impl CopyTask {
+    /// Copies the file or link and returns the number of bytes copied
+    fn run(&self, options: &CopyOptions) -> io::Result<u64> {
+        match self {
+            CopyTask::File { from, to, text } => {
+                let bytes = match text {
+                    Some(text) if text.render && options.transform_text.is_some() => {
+                        copy_text_file(from, to, options.transform_text, text.format)?
+                    }
+                    Some(text) if !text.format.is_unchanged() => {
+                        copy_text_file(from, to, None, text.format)?
+                    }
+                    _ => copy_file(from, to)?,
+                };
+                copy_fs_metadata(from, to, options.preserve_timestamps)?;
+                Ok(bytes)
+            }
+            CopyTask::Symlink { from, to } => copy_symlink(from, to).map(|_| 0),
+        }
+    }
+}
+
+/// Walks the source once, creating the destination's folders and collecting what needs copying
+struct CopyWalker {
+    root: PathBuf,
+    respect_gitignore: bool,
+    symlinks: SymlinkPolicy,
+    exclude: Gitignore,
+    render: Gitignore,
+    raw: Gitignore,
+    text_format: TextFormat,
+    text_overrides: Vec<(Gitignore, TextFormatOverride)>,
+    /// The `.gitignore` matchers of the folders being walked, outermost first
+    gitignores: Vec<Gitignore>,
+    /// The canonical paths of the folders being walked, used to spot symlink loops
+    ancestors: Vec<PathBuf>,
+    /// Every folder created, parents before their children
+    directories: Vec<(PathBuf, PathBuf)>,
+    tasks: Vec<CopyTask>,
+    summary: CopySummary,
+}
+
+impl CopyWalker {
+    fn relative(&self, path: &Path) -> PathBuf {
+        path.strip_prefix(&self.root).unwrap_or(path).to_path_buf()
+    }
+
+    fn skip(&mut self, path: &Path) {
+        let relative = self.relative(path);
+        self.summary.skipped.push(relative);
+    }
+
+    fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if self.exclude.matched(path, is_dir).is_ignore() {
+            return true;
+        }
+
+        // The closest `.gitignore` with an opinion on the path wins, like it does for git
+        self.gitignores
+            .iter()
+            .rev()
+            .map(|matcher| matcher.matched(path, is_dir))
+            .find(|matched| !matched.is_none())
+            .is_some_and(|matched| matched.is_ignore())
+    }
+
+    fn text_copy(&self, path: &Path) -> Option<TextCopy> {
+        if self.raw.matched(path, false).is_ignore() {
+            return None;
+        }
+
+        let is_binary = has_binary_extension(path);
+        let render = if self.render.is_empty() {
+            !is_binary
+        } else {
+            self.render.matched(path, false).is_ignore()
+        };
+        let format = if is_binary {
+            TextFormat::default()
+        } else {
+            self.text_overrides
+                .iter()
+                .filter(|(matcher, _)| matcher.matched(path, false).is_ignore())
+                .fold(self.text_format, |format, (_, text_override)| {
+                    format.with_override(text_override)
+                })
+        };
+
+        (render || !format.is_unchanged()).then_some(TextCopy { render, format })
+    }
+
+    fn walk<From: AsRef<Path>, To: AsRef<Path>>(&mut self, from: From, to: To) -> io::Result<()> {
+        let source_type = fs::metadata(&from)?;
+        let dest_meta = fs::metadata(&to);
+
+        if dest_meta.is_err() {
+            fs::create_dir_all(&to)?;
+        }
+
+        let dest_type = fs::metadata(&to)?;
+
+        // Check if the provided source is a directory whilst the destinations is a file or symlink
+        if source_type.is_dir() && (dest_type.is_file()) {
+            // Print error & exit the process
+            eprintln!("The destination cannot be a file whilst the source is a directory.");
+            process::exit(1);
+        }
+
+        self.directories
+            .push((from.as_ref().to_path_buf(), to.as_ref().to_path_buf()));
+
+        let gitignore = if self.respect_gitignore {
+            build_gitignore_matcher(&from)?
+        } else {
+            None
+        };
+        let pushed_gitignore = gitignore.is_some();
+        self.gitignores.extend(gitignore);
+        self.ancestors.push(fs::canonicalize(&from)?);
+
+        for entry in fs::read_dir(&from)? {
+            let entry = entry?;
+            let mut object_type = entry.file_type()?;
+            let object_path = entry.path();
+            let destination = to.as_ref().join(entry.file_name());
+
+            if object_type.is_symlink() {
+                match self.symlinks {
+                    SymlinkPolicy::Skip => {
+                        self.skip(&object_path);
+                        continue;
+                    }
+                    SymlinkPolicy::Preserve => {
+                        if self.is_ignored(&object_path, false) {
+                            self.skip(&object_path);
+                        } else {
+                            self.tasks.push(CopyTask::Symlink {
+                                from: object_path,
+                                to: destination,
+                            });
+                        }
+                        continue;
+                    }
+                    SymlinkPolicy::Follow => match fs::metadata(&object_path) {
+                        Ok(target_metadata) => object_type = target_metadata.file_type(),
+                        // Dangling links have nothing to follow
+                        Err(_) => {
+                            self.skip(&object_path);
+                            continue;
+                        }
+                    },
+                }
+            }
+
+            if self.is_ignored(&object_path, object_type.is_dir()) {
+                self.skip(&object_path);
+                continue;
+            };
+            if object_type.is_dir() {
+                if self.ancestors.contains(&fs::canonicalize(&object_path)?) {
+                    let relative = self.relative(&object_path);
+                    self.summary.loops.push(relative);
+                    continue;
+                }
+
+                self.walk(object_path, destination)?;
+            } else {
+                self.summary.bytes += fs::metadata(&object_path)?.len();
+                self.tasks.push(CopyTask::File {
+                    text: self.text_copy(&object_path),
+                    from: object_path,
+                    to: destination,
+                });
+            }
+        }
+
+        self.ancestors.pop();
+        if pushed_gitignore {
+            self.gitignores.pop();
+        }
+
+        Ok(())
+    }
+}
+
+/// Copies the contents of `from` into `to`, leaving out anything matching the gitignore-style
+/// excludes, the source's `.ednaignore` file unless it's disabled and, when asked to, its
+/// `.gitignore` files.
+///
+/// The source is walked once and its files are then copied by a bounded pool of threads.
+pub fn copy_fs_objects<From: AsRef<Path>, To: AsRef<Path>>(
+    from: From,
+    to: To,
+    options: &CopyOptions,
+) -> io::Result<CopySummary> {
+    let mut walker = CopyWalker {
+        root: from.as_ref().to_path_buf(),
+        respect_gitignore: options.respect_gitignore,
+        symlinks: options.symlinks,
+        exclude: if options.disable_ednaignore {
+            build_pattern_matcher(&from, options.exclude)?
+        } else {
+            build_exclude_matcher(&from, options.exclude)?
+        },
+        render: build_pattern_matcher(&from, options.render)?,
+        raw: build_pattern_matcher(&from, options.raw)?,
+        text_format: options.text_format,
+        text_overrides: options
+            .text_overrides
+            .iter()
+            .map(|text_override| {
+                build_pattern_matcher(&from, &text_override.files)
+                    .map(|matcher| (matcher, text_override.clone()))
+            })
+            .collect::<io::Result<_>>()?,
+        gitignores: vec![],
+        ancestors: vec![],
+        directories: vec![],
+        tasks: vec![],
+        summary: CopySummary::default(),
+    };
+
+    walker.walk(from, to)?;
+    walker.summary.files = walker.tasks.len();
+
+    run_copy_tasks(
+        &walker.tasks,
+        walker.summary.bytes,
+        available_workers(MAX_COPY_THREADS),
+        options,
+    )?;
+
+    // Children first, since copying into a folder changes its modification time
+    for (from, to) in walker.directories.iter().rev() {
+        copy_fs_metadata(from, to, options.preserve_timestamps)?;
+    }
+
+    Ok(walker.summary)
+}
+
+fn copy_progress_bar(total_bytes: u64, show_progress: bool) -> ProgressBar {
+    if !show_progress {
+        return ProgressBar::hidden();
+    }
+
+    let bar = ProgressBar::new(total_bytes).with_style(
+        ProgressStyle::with_template(
+            "{spinner:.blue} {msg} [{bar:30.cyan/blue}] {bytes}/{total_bytes}",
+        )
+        .unwrap()
+        .progress_chars("=> "),
+    );
+    bar.enable_steady_tick(Duration::from_millis(100));
+    bar
+}
+
+/// Runs the tasks on up to `workers` threads, stopping them all at the first failure or once Ctrl-C
+/// is pressed while it's being listened for
+fn run_copy_tasks(
+    tasks: &[CopyTask],
+    total_bytes: u64,
+    workers: usize,
+    options: &CopyOptions,
+) -> io::Result<()> {
+    let bar = copy_progress_bar(total_bytes, options.show_progress);
+    let copied_files = AtomicUsize::new(0);
+    let failure: Mutex<Option<io::Error>> = Mutex::new(None);
+
+    for_each_parallel(tasks, workers, |_, task| {
+        let copied = if scripts::was_interrupted() {
+            Err(io::Error::new(
+                io::ErrorKind::Interrupted,
+                "the copy was interrupted",
+            ))
+        } else {
+            task.run(options)
+        };
+
+        match copied {
+            Ok(bytes) => {
+                let copied = copied_files.fetch_add(1, Ordering::Relaxed) + 1;
+                bar.inc(bytes);
+                bar.set_message(format!("Copying {}/{} files", copied, tasks.len()));
+                true
+            }
+            Err(error) => {
+                failure.lock().unwrap().get_or_insert(error);
+                false
+            }
+        }
+    });
+
+    bar.finish_and_clear();
+
+    match failure.into_inner().unwrap() {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// Copies a file's contents, cloning it when the filesystem supports it and otherwise relying on
+/// `fs::copy`, which uses `copy_file_range` where it can
+#[cfg(target_os = "linux")]
+fn copy_file(from: &Path, to: &Path) -> io::Result<u64> {
+    use std::os::fd::AsRawFd;
+
+    let source = fs::File::open(from)?;
+    let destination = fs::File::create(to)?;
+
+    // Clones share the source's blocks on filesystems like btrfs and XFS
+    if unsafe { libc::ioctl(destination.as_raw_fd(), libc::FICLONE, source.as_raw_fd()) } == 0 {
+        return source.metadata().map(|metadata| metadata.len());
+    }
+
+    drop(destination);
+    fs::copy(from, to)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn copy_file(from: &Path, to: &Path) -> io::Result<u64> {
+    fs::copy(from, to)
+}
+
+/// Copies a file through `transform` and `format` when its contents are text, or as it is otherwise
+fn copy_text_file(
+    from: &Path,
+    to: &Path,
+    transform: Option<TextTransform>,
+    format: TextFormat,
+) -> io::Result<u64> {
+    let contents = fs::read(from)?;
+    if sniff_content(&contents) == ContentKind::Binary {
+        return copy_file(from, to);
+    }
+
+    let text = String::from_utf8(contents).expect("sniffed text is valid UTF-8");
+    let (body, had_bom) = strip_bom(&text);
+    let formatted = match transform {
+        Some(transform) => format.apply(&transform(body), had_bom),
+        None => format.apply(body, had_bom),
+    };
+    fs::write(to, formatted)?;
+
+    Ok(text.len() as u64)
+}
+
+/// Gives `to` the permissions of `from` and, when asked to, its modification time
+fn copy_fs_metadata<From: AsRef<Path>, To: AsRef<Path>>(
+    from: From,
+    to: To,
+    preserve_timestamps: bool,
+) -> io::Result<()> {
+    let metadata = fs::metadata(&from)?;
+
+    if preserve_timestamps && (metadata.is_file() || cfg!(unix)) {
+        fs::File::options()
+            .write(metadata.is_file())
+            .read(metadata.is_dir())
+            .open(&to)?
+            .set_modified(metadata.modified()?)?;
+    }
+
+    fs::set_permissions(to, metadata.permissions())
+}
+
+#[cfg(unix)]
+fn copy_symlink<From: AsRef<Path>, To: AsRef<Path>>(from: From, to: To) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(from)?, to)
+}
+
+#[cfg(windows)]
+fn copy_symlink<From: AsRef<Path>, To: AsRef<Path>>(from: From, to: To) -> io::Result<()> {
+    let target = fs::read_link(&from)?;
+    if fs::metadata(&from).is_ok_and(|metadata| metadata.is_dir()) {
+        std::os::windows::fs::symlink_dir(target, to)
+    } else {
+        std::os::windows::fs::symlink_file(target, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn excludes_gitignore_style_patterns_and_ednaignore() {
+        let root = TempDir::new().unwrap();
+        fs::write(root.path().join(IGNORE_FILENAME), "*.log\n!keep.log\n").unwrap();
+        let secret = root.path().join("secret");
+
+        let matcher = build_exclude_matcher(
+            root.path(),
+            &[
+                String::from("**/target"),
+                secret.to_str().unwrap().to_string(),
+            ],
+        )
+        .unwrap();
+        let ignored = |path: &str, is_dir: bool| matcher.matched(path, is_dir).is_ignore();
+
+        assert!(ignored("target", true));
+        assert!(ignored("crates/api/target", true));
+        assert!(ignored("logs/build.log", false));
+        assert!(!ignored("keep.log", false));
+        // Absolute paths from older configs only match at the root
+        assert!(ignored("secret", false));
+        assert!(!ignored("docs/secret", false));
+        assert!(ignored(IGNORE_FILENAME, false));
+        assert!(!ignored("src/main.rs", false));
+    }
+
+    #[test]
+    fn leaves_excluded_paths_out_of_copies() {
+        let source = TempDir::new().unwrap();
+        let destination = TempDir::new().unwrap();
+        for file in ["src/main.rs", "target/debug/app", "app.log", "keep.log"] {
+            let path = source.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "contents").unwrap();
+        }
+        fs::write(source.path().join(IGNORE_FILENAME), "*.log\n!keep.log\n").unwrap();
+
+        let summary = copy_fs_objects(
+            source.path(),
+            destination.path(),
+            &CopyOptions {
+                exclude: &[String::from("**/target")],
+                ..CopyOptions::default()
+            },
+        )
+        .unwrap();
+
+        assert!(destination.path().join("src/main.rs").is_file());
+        assert!(destination.path().join("keep.log").is_file());
+        assert!(!destination.path().join("target").exists());
+        assert!(!destination.path().join("app.log").exists());
+        assert!(!destination.path().join(IGNORE_FILENAME).exists());
+        assert_eq!(summary.files, 2);
+    }
+
+    /// A source with a file, a folder, links to both and a link from the folder back to the root
+    #[cfg(unix)]
+    fn linked_source() -> TempDir {
+        use std::os::unix::fs::{PermissionsExt, symlink};
+
+        let source = TempDir::new().unwrap();
+        fs::create_dir(source.path().join("dir")).unwrap();
+        fs::write(source.path().join("a.sh"), "echo a").unwrap();
+        fs::set_permissions(
+            source.path().join("a.sh"),
+            fs::Permissions::from_mode(0o750),
+        )
+        .unwrap();
+        fs::write(source.path().join("dir/inner.txt"), "inner").unwrap();
+        symlink("a.sh", source.path().join("link.sh")).unwrap();
+        symlink("dir", source.path().join("dir_link")).unwrap();
+        symlink("..", source.path().join("dir/loop")).unwrap();
+        source
+    }
+
+    #[cfg(unix)]
+    fn copy_with(source: &Path, options: &CopyOptions) -> (TempDir, CopySummary) {
+        let destination = TempDir::new().unwrap();
+        let summary = copy_fs_objects(source, destination.path(), options).unwrap();
+        (destination, summary)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn preserves_symlinks_and_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let source = linked_source();
+        let (destination, summary) = copy_with(source.path(), &CopyOptions::default());
+
+        assert_eq!(
+            fs::read_link(destination.path().join("link.sh")).unwrap(),
+            Path::new("a.sh")
+        );
+        assert_eq!(
+            fs::read_link(destination.path().join("dir/loop")).unwrap(),
+            Path::new("..")
+        );
+        assert_eq!(
+            fs::metadata(destination.path().join("a.sh"))
+                .unwrap()
+                .permissions()
+                .mode()
+                & 0o777,
+            0o750
+        );
+        assert!(summary.loops.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn follows_symlinks_without_looping() {
+        let source = linked_source();
+        let (destination, summary) = copy_with(
+            source.path(),
+            &CopyOptions {
+                symlinks: SymlinkPolicy::Follow,
+                ..CopyOptions::default()
+            },
+        );
+
+        let link = destination.path().join("link.sh");
+        assert!(!fs::symlink_metadata(&link).unwrap().is_symlink());
+        assert_eq!(fs::read_to_string(link).unwrap(), "echo a");
+        assert_eq!(
+            fs::read_to_string(destination.path().join("dir_link/inner.txt")).unwrap(),
+            "inner"
+        );
+        assert!(summary.loops.contains(&PathBuf::from("dir/loop")));
+        assert!(summary.loops.contains(&PathBuf::from("dir_link/loop")));
+        assert!(!destination.path().join("dir/loop").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn skips_symlinks() {
+        let source = linked_source();
+        let (destination, summary) = copy_with(
+            source.path(),
+            &CopyOptions {
+                symlinks: SymlinkPolicy::Skip,
+                ..CopyOptions::default()
+            },
+        );
+
+        for link in ["link.sh", "dir_link", "dir/loop"] {
+            assert!(fs::symlink_metadata(destination.path().join(link)).is_err());
+            assert!(summary.skipped.contains(&PathBuf::from(link)));
+        }
+        assert!(destination.path().join("a.sh").is_file());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn preserves_modification_times_when_asked_to() {
+        use std::time::SystemTime;
+
+        let source = TempDir::new().unwrap();
+        fs::create_dir(source.path().join("dir")).unwrap();
+        fs::write(source.path().join("dir/file.txt"), "text").unwrap();
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        for (path, is_dir) in [("dir/file.txt", false), ("dir", true)] {
+            fs::File::options()
+                .write(!is_dir)
+                .read(is_dir)
+                .open(source.path().join(path))
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+        }
+
+        let modified_at =
+            |root: &Path, path: &str| fs::metadata(root.join(path)).unwrap().modified().unwrap();
+        let (kept, _) = copy_with(
+            source.path(),
+            &CopyOptions {
+                preserve_timestamps: true,
+                ..CopyOptions::default()
+            },
+        );
+        let (fresh, _) = copy_with(source.path(), &CopyOptions::default());
+
+        assert_eq!(modified_at(kept.path(), "dir/file.txt"), modified);
+        assert_eq!(modified_at(kept.path(), "dir"), modified);
+        assert_ne!(modified_at(fresh.path(), "dir/file.txt"), modified);
+    }
+
+    #[test]
+    fn copies_many_files_in_parallel() {
+        let source = TempDir::new().unwrap();
+        let destination = TempDir::new().unwrap();
+        for index in 0..300 {
+            let path = source
+                .path()
+                .join(format!("{}/file-{}.txt", index % 7, index));
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, format!("file {}", index)).unwrap();
+        }
+
+        let summary =
+            copy_fs_objects(source.path(), destination.path(), &CopyOptions::default()).unwrap();
+
+        assert_eq!(summary.files, 300);
+        for index in 0..300 {
+            let path = destination
+                .path()
+                .join(format!("{}/file-{}.txt", index % 7, index));
+            assert_eq!(fs::read_to_string(path).unwrap(), format!("file {}", index));
+        }
+    }
+
+    #[test]
+    fn stops_copying_at_the_first_failure() {
+        let source = TempDir::new().unwrap();
+        let destination = TempDir::new().unwrap();
+        let mut tasks = vec![CopyTask::File {
+            from: source.path().join("missing.txt"),
+            to: destination.path().join("missing.txt"),
+            text: None,
+        }];
+        for index in 0..50 {
+            let name = format!("file-{}.txt", index);
+            fs::write(source.path().join(&name), "contents").unwrap();
+            tasks.push(CopyTask::File {
+                from: source.path().join(&name),
+                to: destination.path().join(&name),
+                text: None,
+            });
+        }
+
+        let error = run_copy_tasks(&tasks, 0, 1, &CopyOptions::default()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        assert_eq!(fs::read_dir(destination.path()).unwrap().count(), 0);
+
+        let error = run_copy_tasks(&tasks, 0, 4, &CopyOptions::default()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -1,44 +1,86 @@
-use std::{fs, io, path::Path, process};
+use std::{
+    fs, io,
+    path::{Component, Path, PathBuf},
+    process,
+};
+
+pub mod content;
+pub mod copy;
+pub mod patch;
 
 pub fn create_empty_directory<At: AsRef<Path>>(path: At) -> io::Result<()> {
     fs::create_dir(path)?;
     Ok(())
 }
 
-pub fn copy_fs_objects<From: AsRef<Path>, To: AsRef<Path>>(
-    from: From,
-    to: To,
-    exclude: &Vec<String>,
-) -> io::Result<()> {
-    let source_type = fs::metadata(&from)?;
-    let dest_meta = fs::metadata(&to);
+/// Whether `path` is a relative path made of plain names only, so that it can't lead outside the
+/// folder it's joined to
+pub fn is_contained_path<At: AsRef<Path>>(path: At) -> bool {
+    let path = path.as_ref();
+    path.components().next().is_some()
+        && path
+            .components()
+            .all(|part| matches!(part, Component::Normal(_)))
+}
 
-    if dest_meta.is_err() {
-        fs::create_dir_all(&to)?;
+fn is_empty_directory<At: AsRef<Path>>(path: At) -> bool {
+    fs::read_dir(path).is_ok_and(|mut entries| entries.next().is_none())
+}
+
+/// Creates a staging directory next to `target` and returns the path the project should be built in.
+///
+/// The staged path keeps the same final component as `target`, so scripts that derive names from
+/// the current directory behave the same as they would in the final location.
+pub fn create_staging_directory<At: AsRef<Path>>(target: At) -> io::Result<PathBuf> {
+    let target = target.as_ref();
+    if fs::exists(target)? && !is_empty_directory(target) {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists and is not empty", target.display()),
+        ));
     }
 
-    let dest_type = fs::metadata(&to)?;
+    let parent = match target.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let file_name = target.file_name().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not a valid project path", target.display()),
+        )
+    })?;
+
+    let staging_root = parent.join(format!(".edna-staging-{}", process::id()));
+    let staged_path = staging_root.join(file_name);
+    fs::create_dir_all(&staged_path)?;
 
-    // Check if the provided source is a directory whilst the destinations is a file or symlink
-    if source_type.is_dir() && (dest_type.is_file()) {
-        // Print error & exit the process
-        eprintln!("The destination cannot be a file whilst the source is a directory.");
-        process::exit(1);
+    Ok(staged_path)
+}
+
+/// Moves a staged project into `target` and removes what is left of its staging directory
+pub fn promote_staging_directory<From: AsRef<Path>, To: AsRef<Path>>(
+    staged: From,
+    target: To,
+) -> io::Result<()> {
+    let target = target.as_ref();
+    if is_empty_directory(target) {
+        fs::remove_dir(target)?;
     }
 
-    for entry in fs::read_dir(from)? {
-        let entry = entry?;
-        let object_type = entry.file_type()?;
-        let object_path = entry.path();
-        if exclude.contains(&object_path.to_str().unwrap().to_string()) {
-            continue;
-        };
-        if object_type.is_dir() {
-            copy_fs_objects(object_path, to.as_ref().join(entry.file_name()), &exclude)?;
-        } else {
-            fs::copy(object_path, to.as_ref().join(entry.file_name()))?;
-        }
+    fs::rename(&staged, target)?;
+    remove_staging_directory(staged)
+}
+
+/// Removes a staged project together with its staging directory
+pub fn remove_staging_directory<At: AsRef<Path>>(staged: At) -> io::Result<()> {
+    let staged = staged.as_ref();
+    if fs::exists(staged)? {
+        fs::remove_dir_all(staged)?;
     }
 
-    Ok(())
+    match staged.parent() {
+        Some(staging_root) if fs::exists(staging_root)? => fs::remove_dir_all(staging_root),
+        _ => Ok(()),
+    }
 }
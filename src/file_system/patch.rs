@@ -0,0 +1,449 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use toml_edit::{Array, DocumentMut, InlineTable, Item, Table, TableLike};
+
+use super::{copy::TextTransform, is_contained_path};
+
+/// A change to a file of a project, which does nothing when the file already has it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum FilePatch {
+    /// Adds `content` to the end of the file, creating it when needed
+    Append { file: String, content: String },
+    /// Adds `content` after the first line containing `marker`
+    InsertAfter {
+        file: String,
+        marker: String,
+        content: String,
+    },
+    /// Merges `value` into a JSON or TOML file, keeping the keys it doesn't set
+    Merge { file: String, value: Value },
+    /// Adds a dependency to a `Cargo.toml` or `package.json`, leaving one that's already there alone
+    Dependency {
+        file: String,
+        name: String,
+        version: String,
+        #[serde(default)]
+        dev: bool,
+    },
+}
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// Reads a file that a patch may create, treating a missing one as empty
+fn read_optional(path: &Path) -> io::Result<String> {
+    match fs::read_to_string(path) {
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        result => result,
+    }
+}
+
+/// Ends `content` with a line break, so that it's added as whole lines
+fn as_lines(content: &str) -> String {
+    format!("{}\n", content.trim_end_matches('\n'))
+}
+
+impl FilePatch {
+    /// The patched file, relative to the project's root
+    pub fn file(&self) -> &str {
+        match self {
+            FilePatch::Append { file, .. }
+            | FilePatch::InsertAfter { file, .. }
+            | FilePatch::Merge { file, .. }
+            | FilePatch::Dependency { file, .. } => file,
+        }
+    }
+
+    /// Applies the patch to the project at `root`, passing the text it adds through `render`, and
+    /// returns whether the file changed
+    pub fn apply(&self, root: &Path, render: TextTransform) -> io::Result<bool> {
+        if !is_contained_path(self.file()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the file must be relative and stay inside the project",
+            ));
+        }
+
+        let path = root.join(self.file());
+        let original = read_optional(&path)?;
+
+        let patched = match self {
+            FilePatch::Append { content, .. } => {
+                let content = render(content);
+                if original.contains(content.trim_end_matches('\n')) {
+                    return Ok(false);
+                }
+
+                let separator = if original.is_empty() || original.ends_with('\n') {
+                    ""
+                } else {
+                    "\n"
+                };
+                format!("{}{}{}", original, separator, as_lines(&content))
+            }
+            FilePatch::InsertAfter {
+                marker, content, ..
+            } => {
+                let content = render(content);
+                if original.contains(content.trim_end_matches('\n')) {
+                    return Ok(false);
+                }
+
+                insert_after(&original, marker, &as_lines(&content))
+                    .ok_or_else(|| invalid_data(format!("the marker `{}` was not found", marker)))?
+            }
+            FilePatch::Merge { file, value } => {
+                let value = render_strings(value, render);
+                match Path::new(file)
+                    .extension()
+                    .and_then(|extension| extension.to_str())
+                {
+                    Some("json") => merge_json_file(&original, &value)?,
+                    Some("toml") => merge_toml_file(&original, &value)?,
+                    _ => return Err(invalid_data("only JSON and TOML files can be merged into")),
+                }
+            }
+            FilePatch::Dependency {
+                file,
+                name,
+                version,
+                dev,
+            } => {
+                let version = render(version);
+                match Path::new(file).file_name().and_then(|name| name.to_str()) {
+                    Some("Cargo.toml") => {
+                        let table = if *dev {
+                            "dev-dependencies"
+                        } else {
+                            "dependencies"
+                        };
+                        add_toml_dependency(&original, table, name, &version)?
+                    }
+                    Some("package.json") => {
+                        let key = if *dev {
+                            "devDependencies"
+                        } else {
+                            "dependencies"
+                        };
+                        let mut manifest: Value = serde_json::from_str(&original)?;
+                        if manifest
+                            .get(key)
+                            .and_then(|existing| existing.get(name))
+                            .is_some()
+                        {
+                            return Ok(false);
+                        }
+
+                        merge_json(&mut manifest, &json!({ key: { name: version } }));
+                        to_json_string(&manifest)?
+                    }
+                    _ => {
+                        return Err(invalid_data(
+                            "dependencies can only be added to a Cargo.toml or package.json",
+                        ));
+                    }
+                }
+            }
+        };
+
+        if patched == original {
+            return Ok(false);
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, patched)?;
+
+        Ok(true)
+    }
+}
+
+fn insert_after(text: &str, marker: &str, lines: &str) -> Option<String> {
+    let mut patched = String::with_capacity(text.len() + lines.len());
+    let mut inserted = false;
+
+    for line in text.split_inclusive('\n') {
+        patched.push_str(line);
+        if !inserted && line.contains(marker) {
+            if !line.ends_with('\n') {
+                patched.push('\n');
+            }
+            patched.push_str(lines);
+            inserted = true;
+        }
+    }
+
+    inserted.then_some(patched)
+}
+
+fn render_strings(value: &Value, render: TextTransform) -> Value {
+    match value {
+        Value::String(text) => Value::String(render(text)),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| render_strings(item, render))
+                .collect(),
+        ),
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .map(|(key, item)| (key.clone(), render_strings(item, render)))
+                .collect(),
+        ),
+        value => value.clone(),
+    }
+}
+
+fn merge_json(target: &mut Value, value: &Value) {
+    if let (Value::Object(target), Value::Object(value)) = (&mut *target, value) {
+        for (key, item) in value {
+            merge_json(target.entry(key.clone()).or_insert(Value::Null), item);
+        }
+    } else {
+        *target = value.clone();
+    }
+}
+
+fn to_json_string(value: &Value) -> io::Result<String> {
+    Ok(format!("{}\n", serde_json::to_string_pretty(value)?))
+}
+
+fn merge_json_file(original: &str, value: &Value) -> io::Result<String> {
+    if original.trim().is_empty() {
+        return to_json_string(value);
+    }
+
+    let document: Value = serde_json::from_str(original)?;
+    let mut merged = document.clone();
+    merge_json(&mut merged, value);
+
+    // Leaves the file's formatting alone when there's nothing to add
+    if merged == document {
+        return Ok(original.to_string());
+    }
+
+    to_json_string(&merged)
+}
+
+fn to_toml_value(value: &Value) -> io::Result<toml_edit::Value> {
+    Ok(match value {
+        Value::Null => return Err(invalid_data("TOML has no null values")),
+        Value::Bool(flag) => (*flag).into(),
+        Value::Number(number) => match number.as_i64() {
+            Some(integer) => integer.into(),
+            None => number.as_f64().unwrap_or_default().into(),
+        },
+        Value::String(text) => text.as_str().into(),
+        Value::Array(items) => items
+            .iter()
+            .map(to_toml_value)
+            .collect::<io::Result<Array>>()?
+            .into(),
+        Value::Object(object) => {
+            let mut table = InlineTable::new();
+            for (key, item) in object {
+                table.insert(key, to_toml_value(item)?);
+            }
+            table.into()
+        }
+    })
+}
+
+fn merge_toml(table: &mut dyn TableLike, value: &Map<String, Value>) -> io::Result<()> {
+    for (key, item) in value {
+        let existing = table
+            .get_mut(key)
+            .and_then(|existing| existing.as_table_like_mut());
+        match (item, existing) {
+            (Value::Object(object), Some(existing)) => merge_toml(existing, object)?,
+            (Value::Object(object), None) => {
+                let mut nested = Table::new();
+                merge_toml(&mut nested, object)?;
+                table.insert(key, Item::Table(nested));
+            }
+            (item, _) => {
+                let item = to_toml_value(item)?;
+                let unchanged = table
+                    .get(key)
+                    .and_then(|existing| existing.as_value())
+                    .is_some_and(|existing| existing.to_string().trim() == item.to_string().trim());
+                if !unchanged {
+                    table.insert(key, Item::Value(item));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn merge_toml_file(original: &str, value: &Value) -> io::Result<String> {
+    let Value::Object(object) = value else {
+        return Err(invalid_data("only tables can be merged into a TOML file"));
+    };
+
+    let mut document = original.parse::<DocumentMut>().map_err(invalid_data)?;
+    merge_toml(document.as_table_mut(), object)?;
+
+    Ok(document.to_string())
+}
+
+fn add_toml_dependency(
+    original: &str,
+    table: &str,
+    name: &str,
+    version: &str,
+) -> io::Result<String> {
+    let mut document = original.parse::<DocumentMut>().map_err(invalid_data)?;
+    let dependencies = document
+        .entry(table)
+        .or_insert(Item::Table(Table::new()))
+        .as_table_like_mut()
+        .ok_or_else(|| invalid_data(format!("`{}` is not a table", table)))?;
+
+    if !dependencies.contains_key(name) {
+        dependencies.insert(name, toml_edit::value(version));
+    }
+
+    Ok(document.to_string())
+}
+
+/// Applies `patches` to the project at `root` in order
+pub fn apply_patches(root: &Path, patches: &[FilePatch], render: TextTransform) -> io::Result<()> {
+    for patch in patches {
+        patch.apply(root, render).map_err(|error| {
+            io::Error::new(error.kind(), format!("{}: {}", patch.file(), error))
+        })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn apply_twice(root: &Path, patches: &[FilePatch]) {
+        let render = |text: &str| text.replace("{{ name }}", "api");
+        for _ in 0..2 {
+            apply_patches(root, patches, &render).unwrap();
+        }
+    }
+
+    #[test]
+    fn adds_text_once() {
+        let project = TempDir::new().unwrap();
+        fs::write(
+            project.path().join("main.rs"),
+            "mod cli;\n// mods\nfn main() {}",
+        )
+        .unwrap();
+
+        apply_twice(
+            project.path(),
+            &[
+                FilePatch::InsertAfter {
+                    file: String::from("main.rs"),
+                    marker: String::from("// mods"),
+                    content: String::from("mod {{ name }};"),
+                },
+                FilePatch::Append {
+                    file: String::from(".gitignore"),
+                    content: String::from("/target\n"),
+                },
+            ],
+        );
+
+        assert_eq!(
+            fs::read_to_string(project.path().join("main.rs")).unwrap(),
+            "mod cli;\n// mods\nmod api;\nfn main() {}"
+        );
+        assert_eq!(
+            fs::read_to_string(project.path().join(".gitignore")).unwrap(),
+            "/target\n"
+        );
+    }
+
+    #[test]
+    fn merges_keys_and_dependencies_once() {
+        let project = TempDir::new().unwrap();
+        fs::write(
+            project.path().join("Cargo.toml"),
+            "[package]\nname = \"api\" # kept\n\n[dependencies]\nserde = \"1\"\n",
+        )
+        .unwrap();
+        fs::write(
+            project.path().join("package.json"),
+            r#"{"version": "1.0.0", "name": "api"}"#,
+        )
+        .unwrap();
+
+        apply_twice(
+            project.path(),
+            &[
+                FilePatch::Dependency {
+                    file: String::from("Cargo.toml"),
+                    name: String::from("clap"),
+                    version: String::from("4"),
+                    dev: false,
+                },
+                FilePatch::Dependency {
+                    file: String::from("Cargo.toml"),
+                    name: String::from("serde"),
+                    version: String::from("2"),
+                    dev: false,
+                },
+                FilePatch::Merge {
+                    file: String::from("Cargo.toml"),
+                    value: json!({ "package": { "edition": "2024" } }),
+                },
+                FilePatch::Dependency {
+                    file: String::from("package.json"),
+                    name: String::from("vite"),
+                    version: String::from("^5"),
+                    dev: true,
+                },
+            ],
+        );
+
+        assert_eq!(
+            fs::read_to_string(project.path().join("Cargo.toml")).unwrap(),
+            "[package]\nname = \"api\" # kept\nedition = \"2024\"\n\n[dependencies]\nserde = \"1\"\nclap = \"4\"\n"
+        );
+        assert_eq!(
+            fs::read_to_string(project.path().join("package.json")).unwrap(),
+            "{\n  \"version\": \"1.0.0\",\n  \"name\": \"api\",\n  \"devDependencies\": {\n    \"vite\": \"^5\"\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn rejects_files_outside_the_project() {
+        let project = TempDir::new().unwrap();
+        let root = project.path().join("api");
+        fs::create_dir(&root).unwrap();
+
+        for file in [
+            "../outside.txt",
+            "/tmp/outside.txt",
+            "src/../../outside.txt",
+        ] {
+            let patch = FilePatch::Append {
+                file: file.to_string(),
+                content: String::from("text"),
+            };
+            let error = patch
+                .apply(&root, &|text: &str| text.to_string())
+                .unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        }
+        assert!(!project.path().join("outside.txt").exists());
+    }
+}
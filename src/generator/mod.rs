@@ -0,0 +1,1048 @@
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    io::{self, Write},
+    iter,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use console::{Emoji, style};
+use indicatif::ProgressBar;
+use render::render_placeholders;
+
+use crate::{
+    file_system::{
+        content::TextFormat,
+        copy::{CopyOptions, copy_fs_objects},
+        create_empty_directory, create_staging_directory, is_contained_path,
+        patch::apply_patches,
+        promote_staging_directory, remove_staging_directory,
+    },
+    projects::config::RegisteredProject,
+    scripts::{self, ScriptStatus},
+    templates::{
+        config::{CreatedTemplateConfig, FRAGMENT_TARGET, PROJECT_TARGET, TemplateComponent},
+        inheritance::{ExtendsError, TemplateLayers},
+        resolver::{ResolveError, ResolvedTemplate, TemplateResolver},
+    },
+};
+
+#[derive(Debug)]
+pub enum GenerationError {
+    Resolve(ResolveError),
+    Extends(ExtendsError),
+    /// The template is meant for something else, like a fragment used as a project
+    Target {
+        template: String,
+        expected: &'static str,
+    },
+    Staging(io::Error),
+    Copy(io::Error),
+    Scripts(ScriptStatus),
+    /// A script's interpreter couldn't be started, e.g. because it isn't installed
+    Spawn {
+        step: String,
+        program: String,
+        error: io::Error,
+    },
+    Patch(io::Error),
+    Promote(io::Error),
+    /// A component's path is absolute or leads outside the project
+    ComponentPath(String),
+    /// A sub-project of a multi-project template couldn't be created
+    Component {
+        path: String,
+        error: Box<GenerationError>,
+    },
+}
+
+impl GenerationError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            GenerationError::Scripts(ScriptStatus::Interrupted) => 130,
+            GenerationError::Component { error, .. } => error.exit_code(),
+            _ => 1,
+        }
+    }
+}
+
+impl fmt::Display for GenerationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenerationError::Resolve(error) => write!(f, "{}", error),
+            GenerationError::Extends(error) => write!(f, "{}", error),
+            GenerationError::Target { template, expected } => {
+                write!(
+                    f,
+                    "The template {} can't be used as a {}.",
+                    template, expected
+                )
+            }
+            GenerationError::Staging(error) => {
+                write!(f, "Unable to create the project: {}.", error)
+            }
+            GenerationError::Copy(error) => {
+                write!(
+                    f,
+                    "Something bad happened while creating the project: {}.",
+                    error
+                )
+            }
+            GenerationError::Scripts(ScriptStatus::Interrupted) => {
+                write!(f, "Project creation was interrupted.")
+            }
+            GenerationError::Scripts(_) => write!(f, "The template's scripts did not complete."),
+            GenerationError::Spawn {
+                step,
+                program,
+                error,
+            } => write!(f, "Unable to start {} for {}: {}.", program, step, error),
+            GenerationError::Patch(error) => write!(f, "Unable to patch {}.", error),
+            GenerationError::Promote(error) => {
+                write!(f, "Unable to move the project into place: {}.", error)
+            }
+            GenerationError::ComponentPath(path) => write!(
+                f,
+                "The component path {} must be relative and stay inside the project.",
+                path
+            ),
+            GenerationError::Component { path, error } => {
+                write!(f, "Unable to create the component {}: {}", path, error)
+            }
+        }
+    }
+}
+
+pub mod render;
+
+/// The details collected about the project being created
+#[derive(Debug, Clone, Default)]
+pub struct ProjectAnswers {
+    pub name: String,
+    pub output: String,
+    pub description: String,
+    pub version: String,
+    pub author: String,
+}
+
+impl ProjectAnswers {
+    /// The directory the project is created in, joining the output path and the name unless the
+    /// output already ends with the name
+    pub fn project_path(&self) -> String {
+        if Path::new(&self.output).ends_with(&self.name) {
+            self.output.clone()
+        } else {
+            format!("{}/{}", self.output.trim_end_matches('/'), self.name)
+        }
+    }
+
+    /// The values for the template's placeholders, where the answers win over the template's variables
+    pub fn placeholder_values(
+        &self,
+        variables: &BTreeMap<String, String>,
+    ) -> BTreeMap<String, String> {
+        let mut values = variables.clone();
+        values.extend([
+            (String::from("name"), self.name.clone()),
+            (String::from("description"), self.description.clone()),
+            (String::from("version"), self.version.clone()),
+            (String::from("author"), self.author.clone()),
+        ]);
+        values
+    }
+
+    /// The environment variables exported to the template's scripts, which are the ones commands
+    /// run in the registered project get
+    pub fn environment(&self) -> Vec<(&'static str, String)> {
+        RegisteredProject {
+            description: self.description.clone(),
+            version: self.version.clone(),
+            author: self.author.clone(),
+            ..RegisteredProject::new(&self.name, &self.project_path())
+        }
+        .environment()
+    }
+}
+
+/// A template with the templates it extends, checked to be meant for what it's used as
+struct LoadedTemplate {
+    layers: TemplateLayers,
+    config: CreatedTemplateConfig,
+}
+
+impl LoadedTemplate {
+    fn load(
+        template_path: &str,
+        resolver: &TemplateResolver,
+        target: &'static str,
+    ) -> Result<LoadedTemplate, GenerationError> {
+        let layers =
+            TemplateLayers::resolve(template_path, resolver).map_err(GenerationError::Extends)?;
+        let config = layers.config();
+        if config.target != target {
+            return Err(GenerationError::Target {
+                template: config.name,
+                expected: target,
+            });
+        }
+
+        Ok(LoadedTemplate { layers, config })
+    }
+
+    fn path(&self) -> &str {
+        &self
+            .layers
+            .layers
+            .last()
+            .expect("a template has one layer")
+            .path
+    }
+
+    /// Copies each layer on top of the templates it extends, then applies the template's patches
+    /// and records its tasks
+    fn render(&self, answers: &ProjectAnswers, destination: &Path) -> Result<(), GenerationError> {
+        for (index, layer) in self.layers.layers.iter().enumerate() {
+            let layer_config = CreatedTemplateConfig {
+                exclude_paths: self.layers.exclude_paths(index),
+                ..self.config.clone()
+            };
+            ProjectGenerator::render(&layer.path, &layer_config, answers, destination)?;
+        }
+
+        let values = answers.placeholder_values(&self.config.variables);
+        let transform = |content: &str| render_placeholders(content, &values);
+        let patches = self
+            .config
+            .patches
+            .iter()
+            .cloned()
+            .chain(self.config.tasks_patch())
+            .collect::<Vec<_>>();
+        apply_patches(destination, &patches, &transform).map_err(GenerationError::Patch)
+    }
+}
+
+/// A sub-project of a multi-project template, ready to be generated
+struct LoadedComponent {
+    /// Relative to the project's root
+    path: PathBuf,
+    /// Unset for components that are created as empty folders
+    template: Option<LoadedTemplate>,
+    answers: ProjectAnswers,
+}
+
+pub struct GenerationReport {
+    pub project_path: String,
+    /// The name and version of the template the project was created from
+    pub template: Option<(String, String)>,
+}
+
+/// Creates a project by resolving its template, rendering it into a staging directory, running
+/// the template's hooks and finally moving the result into place
+pub struct ProjectGenerator {
+    pub template: ResolvedTemplate,
+    pub answers: ProjectAnswers,
+    pub keep_on_failure: bool,
+    /// Finds the templates that the template extends
+    pub resolver: TemplateResolver,
+    /// The paths of fragments applied on top of the template
+    pub fragments: Vec<String>,
+}
+
+impl ProjectGenerator {
+    /// Copies the template's files into `destination`, filling in placeholders and normalizing line
+    /// endings in text files
+    pub fn render(
+        template_path: &str,
+        template_config: &CreatedTemplateConfig,
+        answers: &ProjectAnswers,
+        destination: &Path,
+    ) -> Result<(), GenerationError> {
+        let values = answers.placeholder_values(&template_config.variables);
+        let transform = |content: &str| render_placeholders(content, &values);
+
+        let options = CopyOptions {
+            exclude: &template_config.exclude_paths,
+            symlinks: template_config.symlinks,
+            preserve_timestamps: template_config.preserve_timestamps,
+            show_progress: true,
+            transform_text: Some(&transform),
+            render: &template_config.render,
+            raw: &template_config.raw,
+            text_format: TextFormat {
+                line_endings: template_config.line_endings,
+                bom: template_config.bom,
+            },
+            text_overrides: &template_config.text_overrides,
+            ..CopyOptions::default()
+        };
+
+        copy_fs_objects(template_path, destination, &options)
+            .map(|_| ())
+            .map_err(GenerationError::Copy)
+    }
+
+    /// Runs the template's scripts in `project_path`, stopping at the first one that doesn't succeed
+    /// or whose interpreter can't be started
+    pub fn run_hooks(
+        template_path: &str,
+        template_config: &CreatedTemplateConfig,
+        answers: &ProjectAnswers,
+        project_path: &Path,
+    ) -> Result<ScriptStatus, GenerationError> {
+        if template_config.scripts.is_empty() {
+            println!(
+                "\n{} {}",
+                style(Emoji("✅", "✔")).green().bright(),
+                style("No scripts to run.").yellow().bold(),
+            );
+            return Ok(ScriptStatus::Success);
+        }
+
+        scripts::listen_for_interrupts();
+
+        let total_scripts = template_config.scripts.len();
+        let deadline = template_config
+            .timeout
+            .map(|seconds| Instant::now() + Duration::from_secs(seconds));
+
+        for (index, script) in template_config.scripts.iter().enumerate() {
+            let step = format!("step {}/{} `{}`", index + 1, total_scripts, script.label());
+
+            // The script's own timeout applies, but never beyond what's left of the global timeout
+            let remaining =
+                deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            let timeout = match (script.timeout().map(Duration::from_secs), remaining) {
+                (Some(own), Some(remaining)) => Some(own.min(remaining)),
+                (own, remaining) => own.or(remaining),
+            };
+
+            let bar = ProgressBar::new_spinner().with_message(format!(
+                "{} {}",
+                style(Emoji("⚙️", "⚙")).blue().bright(),
+                style(format!("Running {}...", step)).bold()
+            ));
+
+            bar.enable_steady_tick(Duration::from_millis(100));
+
+            let mut command = script.command(template_config.shell.as_deref(), template_path);
+            command.envs(answers.environment());
+            let program = command.get_program().to_string_lossy().to_string();
+            let scripts_output = scripts::run_script(command, project_path, timeout);
+
+            bar.finish_and_clear();
+
+            let scripts_output = match scripts_output {
+                Ok(scripts_output) => scripts_output,
+                Err(error) => {
+                    return Err(GenerationError::Spawn {
+                        step,
+                        program,
+                        error,
+                    });
+                }
+            };
+
+            let _ = io::stdout().write(&scripts_output.stdout);
+            let _ = io::stderr().write(&scripts_output.stderr);
+
+            match scripts_output.status {
+                ScriptStatus::Success => continue,
+                ScriptStatus::Failed => {
+                    eprintln!(
+                        "{} {}",
+                        style(Emoji("❌", "𝗑")).red().bright(),
+                        style(format!("Scripts completed with errors at {}.", step))
+                            .red()
+                            .bold()
+                    );
+                }
+                ScriptStatus::TimedOut => {
+                    eprintln!(
+                        "{} {}",
+                        style(Emoji("⏰", "𝗑")).red().bright(),
+                        style(format!(
+                            "Timed out after {}s while running {}.",
+                            timeout.unwrap_or_default().as_secs(),
+                            step
+                        ))
+                        .red()
+                        .bold()
+                    );
+                }
+                ScriptStatus::Interrupted => {
+                    eprintln!(
+                        "{} {}",
+                        style(Emoji("🛑", "𝗑")).red().bright(),
+                        style(format!("Interrupted while running {}.", step))
+                            .red()
+                            .bold()
+                    );
+                }
+            }
+
+            return Ok(scripts_output.status);
+        }
+
+        println!(
+            "{} {}",
+            style(Emoji("✅", "✔")).green().bright(),
+            style("Scripts completed successfully!").green().bold()
+        );
+
+        Ok(ScriptStatus::Success)
+    }
+
+    /// Runs the scripts of a template and then of each fragment applied on top of it
+    fn run_template_hooks(
+        templates: &[&LoadedTemplate],
+        answers: &ProjectAnswers,
+        project_path: &Path,
+    ) -> Result<ScriptStatus, GenerationError> {
+        for (index, template) in templates.iter().enumerate() {
+            // Fragments without scripts have nothing worth reporting
+            if index > 0 && template.config.scripts.is_empty() {
+                continue;
+            }
+
+            let status = Self::run_hooks(template.path(), &template.config, answers, project_path)?;
+            if status != ScriptStatus::Success {
+                return Ok(status);
+            }
+        }
+
+        Ok(ScriptStatus::Success)
+    }
+
+    /// Applies fragments to the existing project at `project_path`, copying their files over the
+    /// project's and running their scripts in it
+    pub fn add_fragments(
+        fragments: &[String],
+        answers: &ProjectAnswers,
+        resolver: &TemplateResolver,
+        project_path: &Path,
+    ) -> Result<(), GenerationError> {
+        let fragments = fragments
+            .iter()
+            .map(|fragment| LoadedTemplate::load(fragment, resolver, FRAGMENT_TARGET))
+            .collect::<Result<Vec<LoadedTemplate>, GenerationError>>()?;
+
+        for fragment in &fragments {
+            fragment.render(answers, project_path)?;
+        }
+
+        match Self::run_template_hooks(
+            &fragments.iter().collect::<Vec<_>>(),
+            answers,
+            project_path,
+        )? {
+            ScriptStatus::Success => Ok(()),
+            status => Err(GenerationError::Scripts(status)),
+        }
+    }
+
+    /// Resolves a component of `template` through the registry and works out its answers, filling
+    /// in the project's placeholders in the ones it sets
+    fn load_component(
+        &self,
+        template: &LoadedTemplate,
+        component: &TemplateComponent,
+    ) -> Result<LoadedComponent, GenerationError> {
+        let path = PathBuf::from(&component.path);
+        let file_name = match path.file_name() {
+            Some(file_name) if is_contained_path(&path) => file_name.to_string_lossy().to_string(),
+            _ => return Err(GenerationError::ComponentPath(component.path.clone())),
+        };
+        let wrap = |error: GenerationError| GenerationError::Component {
+            path: component.path.clone(),
+            error: Box::new(error),
+        };
+
+        // Relative template paths are taken from the folder of the template declaring them
+        let component_template = match self
+            .resolver
+            .resolve_relative_to(&component.template, template.path())
+        {
+            Ok(ResolvedTemplate::Empty) => None,
+            Ok(ResolvedTemplate::Directory(template_path)) => {
+                let mut loaded =
+                    LoadedTemplate::load(&template_path, &self.resolver, PROJECT_TARGET)
+                        .map_err(wrap)?;
+                // Components are only created one level deep
+                if !loaded.config.components.is_empty() {
+                    return Err(wrap(GenerationError::Target {
+                        template: loaded.config.name,
+                        expected: "component",
+                    }));
+                }
+                loaded.config.variables.extend(component.variables.clone());
+                Some(loaded)
+            }
+            Err(error) => return Err(wrap(GenerationError::Resolve(error))),
+        };
+
+        let values = self.answers.placeholder_values(&template.config.variables);
+        let answer = |own: &Option<String>, project: &String| match own {
+            Some(own) => render_placeholders(own, &values),
+            None => project.clone(),
+        };
+
+        Ok(LoadedComponent {
+            answers: ProjectAnswers {
+                name: file_name,
+                output: Path::new(&self.answers.project_path())
+                    .join(&path)
+                    .to_string_lossy()
+                    .to_string(),
+                description: answer(&component.description, &self.answers.description),
+                version: answer(&component.version, &self.answers.version),
+                author: answer(&component.author, &self.answers.author),
+            },
+            path,
+            template: component_template,
+        })
+    }
+
+    /// Creates a component's files inside the staged project
+    fn render_component(
+        component: &LoadedComponent,
+        staged_path: &Path,
+    ) -> Result<(), GenerationError> {
+        let component_path = staged_path.join(&component.path);
+        let rendered = match &component.template {
+            Some(template) => template.render(&component.answers, &component_path),
+            None => fs::create_dir_all(&component_path).map_err(GenerationError::Copy),
+        };
+
+        rendered.map_err(|error| GenerationError::Component {
+            path: component.path.to_string_lossy().to_string(),
+            error: Box::new(error),
+        })
+    }
+
+    /// Runs every stage of the pipeline, leaving nothing behind at the project's path on failure
+    pub fn generate(&self) -> Result<GenerationReport, GenerationError> {
+        let project_path = self.answers.project_path();
+
+        let template_path = match &self.template {
+            ResolvedTemplate::Empty => {
+                create_empty_directory(&project_path).map_err(GenerationError::Staging)?;
+                return Ok(GenerationReport {
+                    project_path,
+                    template: None,
+                });
+            }
+            ResolvedTemplate::Directory(template_path) => template_path,
+        };
+
+        let template = LoadedTemplate::load(template_path, &self.resolver, PROJECT_TARGET)?;
+        let fragments = self
+            .fragments
+            .iter()
+            .map(|fragment| LoadedTemplate::load(fragment, &self.resolver, FRAGMENT_TARGET))
+            .collect::<Result<Vec<LoadedTemplate>, GenerationError>>()?;
+        let templates = iter::once(&template)
+            .chain(&fragments)
+            .collect::<Vec<&LoadedTemplate>>();
+        let components = template
+            .config
+            .components
+            .iter()
+            .map(|component| self.load_component(&template, component))
+            .collect::<Result<Vec<LoadedComponent>, GenerationError>>()?;
+
+        // Ctrl-C is caught from here on, so that the staged project can be cleaned up
+        scripts::listen_for_interrupts();
+        let staged_path =
+            create_staging_directory(&project_path).map_err(GenerationError::Staging)?;
+
+        let abandon = |error: GenerationError, keep: bool| {
+            if keep {
+                eprintln!(
+                    "The partially created project was kept at {} for debugging.",
+                    staged_path.display()
+                );
+            } else {
+                let _ = remove_staging_directory(&staged_path);
+            }
+
+            Err(error)
+        };
+        let keep_on_interrupt = self.keep_on_failure || !template.config.rollback_on_cancel;
+        let interrupted = || GenerationError::Scripts(ScriptStatus::Interrupted);
+
+        for template in &templates {
+            let rendered = template.render(&self.answers, &staged_path);
+            if scripts::was_interrupted() {
+                return abandon(interrupted(), keep_on_interrupt);
+            }
+            if let Err(error) = rendered {
+                return abandon(error, self.keep_on_failure);
+            }
+        }
+
+        for component in &components {
+            let rendered = Self::render_component(component, &staged_path);
+            if scripts::was_interrupted() {
+                return abandon(interrupted(), keep_on_interrupt);
+            }
+            if let Err(error) = rendered {
+                return abandon(error, self.keep_on_failure);
+            }
+        }
+
+        // Components are set up first, so the project's scripts can rely on them
+        let mut status = Ok(ScriptStatus::Success);
+        for component in &components {
+            if let Some(component_template) = &component.template {
+                status = Self::run_template_hooks(
+                    &[component_template],
+                    &component.answers,
+                    &staged_path.join(&component.path),
+                );
+            }
+            if !matches!(status, Ok(ScriptStatus::Success)) {
+                break;
+            }
+        }
+        if matches!(status, Ok(ScriptStatus::Success)) {
+            status = Self::run_template_hooks(&templates, &self.answers, &staged_path);
+        }
+
+        let status = match status {
+            Ok(status) => status,
+            Err(error) => return abandon(error, self.keep_on_failure),
+        };
+        match status {
+            ScriptStatus::Success => {}
+            ScriptStatus::Interrupted => return abandon(interrupted(), keep_on_interrupt),
+            status => return abandon(GenerationError::Scripts(status), self.keep_on_failure),
+        }
+        if scripts::was_interrupted() {
+            return abandon(interrupted(), keep_on_interrupt);
+        }
+
+        if let Err(error) = promote_staging_directory(&staged_path, &project_path) {
+            return abandon(GenerationError::Promote(error), self.keep_on_failure);
+        }
+
+        Ok(GenerationReport {
+            project_path,
+            template: Some((template.config.name, template.config.version)),
+        })
+    }
+
+    pub fn report(report: &GenerationReport) {
+        println!(
+            "\n{} {}",
+            style(Emoji("📁", "✔")).green().bright(),
+            style(format!("Created {}", report.project_path)).bold(),
+        );
+
+        println!(
+            "\n{} {}",
+            style(Emoji("🚀", "✔")).green().bright(),
+            style("All the best!").yellow().bold(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::templates::config::{RegisteredTemplate, TemplateScript};
+
+    fn template_config(scripts: Vec<TemplateScript>) -> CreatedTemplateConfig {
+        serde_json::from_value::<CreatedTemplateConfig>(serde_json::json!({
+            "target": "project",
+            "name": "test",
+        }))
+        .map(|config| CreatedTemplateConfig { scripts, ..config })
+        .unwrap()
+    }
+
+    fn answers(output: &Path, name: &str) -> ProjectAnswers {
+        ProjectAnswers {
+            name: name.to_string(),
+            output: output.to_str().unwrap().to_string(),
+            ..ProjectAnswers::default()
+        }
+    }
+
+    fn write_template(root: &Path, config: &str) -> String {
+        let template = root.join("basic");
+        fs::create_dir_all(template.join("src")).unwrap();
+        fs::write(template.join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(template.join("edna.config.json"), config).unwrap();
+        template.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn joins_the_output_and_name_once() {
+        let mut answers = answers(Path::new("./out/"), "api");
+        assert_eq!(answers.project_path(), "./out/api");
+
+        answers.output = String::from("./out/api");
+        assert_eq!(answers.project_path(), "./out/api");
+    }
+
+    #[test]
+    fn renders_the_template_without_excluded_paths() {
+        let templates = TempDir::new().unwrap();
+        let template_path = write_template(templates.path(), "{}");
+        let destination = TempDir::new().unwrap();
+
+        let mut config = template_config(vec![]);
+        config
+            .exclude_paths
+            .push(format!("{}/edna.config.json", template_path));
+
+        ProjectGenerator::render(
+            &template_path,
+            &config,
+            &answers(destination.path(), "api"),
+            destination.path(),
+        )
+        .unwrap();
+
+        assert!(destination.path().join("src/main.rs").is_file());
+        assert!(!destination.path().join("edna.config.json").exists());
+    }
+
+    #[test]
+    fn renders_placeholders_in_text_files_only() {
+        let templates = TempDir::new().unwrap();
+        let template_path = write_template(templates.path(), "{}");
+        fs::write(
+            templates.path().join("basic/README.md"),
+            "# {{ name }} by {{author}}, {{ unknown }}",
+        )
+        .unwrap();
+        let image = [
+            0x89, b'P', b'N', b'G', 0, b'{', b'{', b'n', b'a', b'm', b'e', b'}', b'}',
+        ];
+        fs::write(templates.path().join("basic/logo.dat"), image).unwrap();
+        let destination = TempDir::new().unwrap();
+
+        let mut answers = answers(destination.path(), "api");
+        answers.author = String::from("Edna");
+        ProjectGenerator::render(
+            &template_path,
+            &template_config(vec![]),
+            &answers,
+            destination.path(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(destination.path().join("README.md")).unwrap(),
+            "# api by Edna, {{ unknown }}"
+        );
+        assert_eq!(
+            fs::read(destination.path().join("logo.dat")).unwrap(),
+            image
+        );
+    }
+
+    #[test]
+    fn normalizes_line_endings_with_overrides() {
+        let templates = TempDir::new().unwrap();
+        let template_path = write_template(templates.path(), "{}");
+        fs::write(templates.path().join("basic/README.md"), "\u{feff}a\r\nb\n").unwrap();
+        fs::write(templates.path().join("basic/run.bat"), "a\nb\r\n").unwrap();
+        let destination = TempDir::new().unwrap();
+
+        let config = serde_json::from_value::<CreatedTemplateConfig>(serde_json::json!({
+            "target": "project",
+            "name": "test",
+            "line_endings": "lf",
+            "bom": "strip",
+            "text_overrides": [{ "files": ["*.bat"], "line_endings": "crlf" }],
+        }))
+        .unwrap();
+        ProjectGenerator::render(
+            &template_path,
+            &config,
+            &answers(destination.path(), "api"),
+            destination.path(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(destination.path().join("README.md")).unwrap(),
+            "a\nb\n"
+        );
+        assert_eq!(
+            fs::read_to_string(destination.path().join("run.bat")).unwrap(),
+            "a\r\nb\r\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_hooks_with_the_answers_exported() {
+        let project = TempDir::new().unwrap();
+        let config = template_config(vec![TemplateScript::Command(String::from(
+            "printf %s \"$EDNA_PROJECT_NAME\" > name.txt",
+        ))]);
+
+        let status = ProjectGenerator::run_hooks(
+            "",
+            &config,
+            &answers(project.path(), "api"),
+            project.path(),
+        );
+
+        assert_eq!(status.unwrap(), ScriptStatus::Success);
+        assert_eq!(
+            fs::read_to_string(project.path().join("name.txt")).unwrap(),
+            "api"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stops_hooks_at_the_first_failure() {
+        let project = TempDir::new().unwrap();
+        let config = template_config(vec![
+            TemplateScript::Command(String::from("exit 3")),
+            TemplateScript::Command(String::from("touch ran.txt")),
+        ]);
+
+        let status = ProjectGenerator::run_hooks(
+            "",
+            &config,
+            &answers(project.path(), "api"),
+            project.path(),
+        );
+
+        assert_eq!(status.unwrap(), ScriptStatus::Failed);
+        assert!(!project.path().join("ran.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn generates_the_project_in_place() {
+        let templates = TempDir::new().unwrap();
+        let template_path = write_template(
+            templates.path(),
+            r#"{"target": "project", "name": "basic", "exclude_config": true, "scripts": ["touch done.txt"]}"#,
+        );
+        let output = TempDir::new().unwrap();
+
+        let generator = ProjectGenerator {
+            template: ResolvedTemplate::Directory(template_path),
+            answers: answers(output.path(), "api"),
+            keep_on_failure: false,
+            resolver: TemplateResolver::new(vec![]),
+            fragments: vec![],
+        };
+        let report = generator.generate().unwrap();
+
+        let project = Path::new(&report.project_path);
+        assert!(project.join("src/main.rs").is_file());
+        assert!(project.join("done.txt").is_file());
+        assert!(!project.join("edna.config.json").exists());
+        assert_eq!(fs::read_dir(output.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn layers_the_template_over_the_one_it_extends() {
+        let templates = TempDir::new().unwrap();
+        let parent_path = write_template(
+            templates.path(),
+            r#"{"target": "project", "name": "basic", "exclude_config": true, "variables": {"license": "MIT", "edition": "2021"}}"#,
+        );
+        fs::write(
+            templates.path().join("basic/README.md"),
+            "{{ license }} {{ edition }}",
+        )
+        .unwrap();
+        fs::write(templates.path().join("basic/CHANGELOG.md"), "").unwrap();
+
+        let template = templates.path().join("cli");
+        fs::create_dir_all(template.join("src")).unwrap();
+        fs::write(template.join("src/main.rs"), "fn main() { cli() }").unwrap();
+        fs::write(
+            template.join("edna.config.json"),
+            r#"{"target": "project", "name": "cli", "extends": "basic", "exclude_config": true, "exclude_paths": ["CHANGELOG.md"], "variables": {"edition": "2024"}}"#,
+        )
+        .unwrap();
+        let output = TempDir::new().unwrap();
+
+        let generator = ProjectGenerator {
+            template: ResolvedTemplate::Directory(template.to_str().unwrap().to_string()),
+            answers: answers(output.path(), "api"),
+            keep_on_failure: false,
+            resolver: TemplateResolver::new(vec![RegisteredTemplate {
+                name: String::from("basic"),
+                path: parent_path,
+                aliases: vec![],
+                usage: Default::default(),
+            }]),
+            fragments: vec![],
+        };
+        let report = generator.generate().unwrap();
+
+        let project = Path::new(&report.project_path);
+        assert_eq!(
+            fs::read_to_string(project.join("src/main.rs")).unwrap(),
+            "fn main() { cli() }"
+        );
+        assert_eq!(
+            fs::read_to_string(project.join("README.md")).unwrap(),
+            "MIT 2024"
+        );
+        assert!(!project.join("CHANGELOG.md").exists());
+        assert!(!project.join("edna.config.json").exists());
+    }
+
+    #[test]
+    fn adds_fragments_but_rejects_projects_as_fragments() {
+        let templates = TempDir::new().unwrap();
+        let template_path = write_template(
+            templates.path(),
+            r#"{"target": "project", "name": "basic"}"#,
+        );
+        let fragment = templates.path().join("docker");
+        fs::create_dir(&fragment).unwrap();
+        fs::write(fragment.join("Dockerfile"), "# {{ name }}").unwrap();
+        fs::write(
+            fragment.join("edna.config.json"),
+            r#"{"target": "fragment", "name": "docker", "exclude_config": true}"#,
+        )
+        .unwrap();
+        let project = TempDir::new().unwrap();
+        let answers = answers(project.path(), "api");
+        let resolver = TemplateResolver::new(vec![]);
+
+        ProjectGenerator::add_fragments(
+            &[fragment.to_str().unwrap().to_string()],
+            &answers,
+            &resolver,
+            project.path(),
+        )
+        .unwrap();
+        let error =
+            ProjectGenerator::add_fragments(&[template_path], &answers, &resolver, project.path())
+                .unwrap_err();
+
+        assert_eq!(
+            fs::read_to_string(project.path().join("Dockerfile")).unwrap(),
+            "# api"
+        );
+        assert_eq!(
+            error.to_string(),
+            "The template basic can't be used as a fragment."
+        );
+    }
+
+    #[test]
+    fn generates_each_component_with_its_own_answers() {
+        let templates = TempDir::new().unwrap();
+        let basic_path = write_template(
+            templates.path(),
+            r#"{"target": "project", "name": "basic", "exclude_config": true}"#,
+        );
+        fs::write(
+            templates.path().join("basic/README.md"),
+            "{{ name }}: {{ description }}",
+        )
+        .unwrap();
+
+        let template = templates.path().join("monorepo");
+        fs::create_dir(&template).unwrap();
+        fs::write(
+            template.join("edna.config.json"),
+            r#"{
+                "target": "project",
+                "name": "monorepo",
+                "exclude_config": true,
+                "components": [
+                    {"path": "apps/api", "template": "basic", "description": "The {{ name }} API"},
+                    {"path": "packages/shared", "template": "none"},
+                    {"path": "apps/web", "template": "../basic"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let output = TempDir::new().unwrap();
+
+        let generator = ProjectGenerator {
+            template: ResolvedTemplate::Directory(template.to_str().unwrap().to_string()),
+            answers: answers(output.path(), "shop"),
+            keep_on_failure: false,
+            resolver: TemplateResolver::new(vec![RegisteredTemplate {
+                name: String::from("basic"),
+                path: basic_path,
+                aliases: vec![],
+                usage: Default::default(),
+            }]),
+            fragments: vec![],
+        };
+        let report = generator.generate().unwrap();
+
+        let project = Path::new(&report.project_path);
+        assert_eq!(
+            fs::read_to_string(project.join("apps/api/README.md")).unwrap(),
+            "api: The shop API"
+        );
+        assert!(project.join("apps/api/src/main.rs").is_file());
+        assert!(project.join("packages/shared").is_dir());
+        assert!(project.join("apps/web/src/main.rs").is_file());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn leaves_nothing_behind_when_generation_fails() {
+        let templates = TempDir::new().unwrap();
+        let template_path = write_template(
+            templates.path(),
+            r#"{"target": "project", "name": "basic", "scripts": ["exit 1"]}"#,
+        );
+        let output = TempDir::new().unwrap();
+
+        let generator = ProjectGenerator {
+            template: ResolvedTemplate::Directory(template_path),
+            answers: answers(output.path(), "api"),
+            keep_on_failure: false,
+            resolver: TemplateResolver::new(vec![]),
+            fragments: vec![],
+        };
+
+        assert!(matches!(
+            generator.generate(),
+            Err(GenerationError::Scripts(ScriptStatus::Failed))
+        ));
+        assert_eq!(fs::read_dir(output.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn reports_scripts_whose_shell_cannot_be_started() {
+        let templates = TempDir::new().unwrap();
+        let template_path = write_template(
+            templates.path(),
+            r#"{"target": "project", "name": "basic", "shell": "nosuchshell", "scripts": ["exit 0"]}"#,
+        );
+        let output = TempDir::new().unwrap();
+
+        let generator = ProjectGenerator {
+            template: ResolvedTemplate::Directory(template_path),
+            answers: answers(output.path(), "api"),
+            keep_on_failure: false,
+            resolver: TemplateResolver::new(vec![]),
+            fragments: vec![],
+        };
+
+        match generator.generate() {
+            Err(GenerationError::Spawn { program, .. }) => assert_eq!(program, "nosuchshell"),
+            _ => panic!("expected the shell to fail to start"),
+        }
+        assert_eq!(fs::read_dir(output.path()).unwrap().count(), 0);
+    }
+}
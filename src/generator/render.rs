@@ -0,0 +1,31 @@
+use std::collections::BTreeMap;
+
+const PLACEHOLDER_START: &str = "{{";
+const PLACEHOLDER_END: &str = "}}";
+
+/// Replaces `{{ key }}` placeholders with their values. Placeholders without a value are left
+/// untouched, so files using the same syntax for other tools survive.
+pub fn render_placeholders(content: &str, values: &BTreeMap<String, String>) -> String {
+    let mut rendered = String::with_capacity(content.len());
+    let mut remaining = content;
+
+    while let Some(start) = remaining.find(PLACEHOLDER_START) {
+        let after_start = &remaining[start + PLACEHOLDER_START.len()..];
+        let Some(end) = after_start.find(PLACEHOLDER_END) else {
+            break;
+        };
+
+        rendered.push_str(&remaining[..start]);
+        match values.get(after_start[..end].trim()) {
+            Some(value) => rendered.push_str(value),
+            None => rendered.push_str(
+                &remaining[start..start + PLACEHOLDER_START.len() + end + PLACEHOLDER_END.len()],
+            ),
+        }
+
+        remaining = &after_start[end + PLACEHOLDER_END.len()..];
+    }
+
+    rendered.push_str(remaining);
+    rendered
+}
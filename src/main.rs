@@ -1,12 +1,17 @@
 mod cli;
 mod file_system;
+mod generator;
+mod projects;
+mod scripts;
 mod templates;
+mod workers;
 
 use cli::{
     config::{CliMetadata, CliParserOptions},
     parse_cli_args, register_cli_args,
 };
 
+use projects::config::{ProjectsMetadata, user_directory};
 use templates::config::TemplatesMetadata;
 
 fn main() {
@@ -15,11 +20,18 @@ fn main() {
         filename: String::from("edna.config.json"),
     };
 
+    // Kept per user, so that projects are found from any folder
+    let projects_meta = ProjectsMetadata {
+        directory: user_directory(),
+        filename: String::from("edna.projects.json"),
+    };
+
     let arg_matches = register_cli_args();
 
     parse_cli_args(CliParserOptions {
         metadata: &CliMetadata {
             templates_meta: &templates_meta,
+            projects_meta: &projects_meta,
         },
         matches: &arg_matches,
     });
@@ -0,0 +1,183 @@
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{self, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub struct ProjectsMetadata {
+    pub directory: String,
+    pub filename: String,
+}
+
+/// The folder the current user's projects registry is kept in, which is `$EDNA_HOME` when it's
+/// set and an `edna` folder in the user's config folder otherwise
+pub fn user_directory() -> String {
+    let non_empty = |name: &str| env::var_os(name).filter(|value| !value.is_empty());
+    if let Some(home) = non_empty("EDNA_HOME") {
+        return home.to_string_lossy().to_string();
+    }
+
+    let config_directory = if cfg!(windows) {
+        non_empty("APPDATA").map(PathBuf::from)
+    } else {
+        non_empty("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| non_empty("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+
+    match config_directory {
+        Some(directory) => directory.join("edna").to_string_lossy().to_string(),
+        None => String::from("."),
+    }
+}
+
+/// A project managed by edna, whether it was created from a template or not
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RegisteredProject {
+    pub name: String,
+    /// The project's absolute path
+    pub path: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    /// The version the project started out at
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub version: String,
+    /// The name of the template the project was created from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template_version: Option<String>,
+    /// The main language of projects that were imported rather than created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    pub created: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub author: String,
+    /// The person or team responsible for the project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Free-form details about the project, like its team or where it's deployed
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+    /// The archive the project was compressed into, once it's been archived
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived: Option<String>,
+}
+
+impl RegisteredProject {
+    /// A project created now, with nothing but its name and absolute path set
+    pub fn new(name: &str, path: &str) -> RegisteredProject {
+        RegisteredProject {
+            name: name.to_string(),
+            path: path.to_string(),
+            description: String::new(),
+            version: String::new(),
+            template: None,
+            template_version: None,
+            language: None,
+            created: Utc::now(),
+            author: String::new(),
+            owner: None,
+            tags: vec![],
+            metadata: Default::default(),
+            archived: None,
+        }
+    }
+
+    /// The `EDNA_PROJECT_*` variables that commands, tasks and template scripts run in the project
+    /// can read
+    pub fn environment(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("EDNA_PROJECT_NAME", self.name.clone()),
+            ("EDNA_PROJECT_PATH", self.path.clone()),
+            ("EDNA_PROJECT_DESCRIPTION", self.description.clone()),
+            ("EDNA_PROJECT_VERSION", self.version.clone()),
+            ("EDNA_PROJECT_AUTHOR", self.author.clone()),
+        ]
+    }
+
+    /// Adds the tags the project doesn't have yet, keeping their order
+    pub fn add_tags<'a>(&mut self, tags: impl IntoIterator<Item = &'a String>) {
+        for tag in tags {
+            if !self.tags.contains(tag) {
+                self.tags.push(tag.clone());
+            }
+        }
+    }
+}
+
+/// Parses a `key=value` metadata entry
+pub fn parse_metadata_entry(entry: &str) -> Result<(String, String), String> {
+    match entry.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.trim().to_string()))
+        }
+        _ => Err(format!("{} is not a `key=value` pair", entry)),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProjectsConfig {
+    pub target: String,
+    pub registry: Vec<RegisteredProject>,
+    /// The command `edna open` launches, taking precedence over `$VISUAL` and `$EDITOR`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub editor: Option<String>,
+}
+
+impl Default for ProjectsConfig {
+    fn default() -> Self {
+        ProjectsConfig {
+            target: String::from("projects"),
+            registry: vec![],
+            editor: None,
+        }
+    }
+}
+
+impl ProjectsConfig {
+    pub fn get_path(metadata: &ProjectsMetadata) -> String {
+        let path_buffer = path::absolute(&metadata.directory)
+            .unwrap()
+            .join(&metadata.filename);
+
+        path_buffer.to_str().unwrap().to_string()
+    }
+
+    /// Loads the registry, which is empty until the first project is registered
+    pub fn load(metadata: &ProjectsMetadata) -> io::Result<ProjectsConfig> {
+        match fs::File::open(Self::get_path(metadata)) {
+            Ok(file) => serde_json::from_reader(file).map_err(io::Error::from),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(ProjectsConfig::default()),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn save(&self, metadata: &ProjectsMetadata) -> io::Result<()> {
+        let path = Self::get_path(metadata);
+        if let Some(directory) = path::Path::new(&path).parent() {
+            fs::create_dir_all(directory)?;
+        }
+
+        let file_writer = fs::File::create(path)?;
+        serde_json::to_writer(file_writer, self).map_err(io::Error::from)
+    }
+
+    /// Adds a project to the registry, replacing any project previously registered at its path
+    pub fn register_project(
+        project: &RegisteredProject,
+        metadata: &ProjectsMetadata,
+    ) -> io::Result<()> {
+        let mut config = Self::load(metadata)?;
+        config
+            .registry
+            .retain(|registered| registered.path != project.path);
+        config.registry.push(project.clone());
+        config.save(metadata)
+    }
+}
@@ -0,0 +1,144 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use toml_edit::DocumentMut;
+
+/// The files that mark a folder as a project's root, along with the language they suggest, in
+/// the order they're looked for
+const PROJECT_MARKERS: [(&str, Option<&str>); 5] = [
+    ("Cargo.toml", Some("rust")),
+    ("package.json", Some("javascript")),
+    ("pyproject.toml", Some("python")),
+    ("go.mod", Some("go")),
+    (".git", None),
+];
+
+/// Folders that are never looked into when scanning for projects
+const SKIPPED_DIRECTORIES: [&str; 4] = ["node_modules", "target", "vendor", "dist"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedProject {
+    pub name: String,
+    pub path: PathBuf,
+    pub language: Option<String>,
+}
+
+/// The name a manifest gives its project, if it gives one
+fn manifest_name(manifest: &Path) -> Option<String> {
+    let contents = fs::read_to_string(manifest).ok()?;
+
+    match manifest.file_name()?.to_str()? {
+        "Cargo.toml" => {
+            let document = contents.parse::<DocumentMut>().ok()?;
+            document
+                .get("package")?
+                .get("name")?
+                .as_str()
+                .map(String::from)
+        }
+        "pyproject.toml" => {
+            let document = contents.parse::<DocumentMut>().ok()?;
+            let project = document.get("project");
+            let poetry = document.get("tool").and_then(|tool| tool.get("poetry"));
+            project.or(poetry)?.get("name")?.as_str().map(String::from)
+        }
+        "package.json" => {
+            let manifest: serde_json::Value = serde_json::from_str(&contents).ok()?;
+            manifest.get("name")?.as_str().map(String::from)
+        }
+        "go.mod" => contents
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("module "))
+            .and_then(|module| module.trim().rsplit('/').next())
+            .map(String::from),
+        _ => None,
+    }
+}
+
+/// Recognises `directory` as a project's root from the files in it, inferring its name and
+/// language from its manifest
+pub fn detect_project(directory: &Path) -> Option<DetectedProject> {
+    let (marker, language) = PROJECT_MARKERS
+        .iter()
+        .find(|(marker, _)| directory.join(marker).exists())?;
+
+    let folder_name = directory.file_name()?.to_string_lossy().to_string();
+    Some(DetectedProject {
+        name: manifest_name(&directory.join(marker)).unwrap_or(folder_name),
+        path: directory.to_path_buf(),
+        language: language.map(String::from),
+    })
+}
+
+/// Finds the projects in `root` and up to `depth` levels of folders below it. Folders inside a
+/// project aren't looked into, so a workspace is found rather than each of its members.
+pub fn scan_projects(root: &Path, depth: usize) -> io::Result<Vec<DetectedProject>> {
+    if let Some(project) = detect_project(root) {
+        return Ok(vec![project]);
+    }
+
+    let mut projects = vec![];
+    if depth == 0 {
+        return Ok(projects);
+    }
+
+    let mut entries = fs::read_dir(root)?.collect::<io::Result<Vec<fs::DirEntry>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let hidden = file_name.starts_with('.');
+        if !entry.file_type()?.is_dir()
+            || hidden
+            || SKIPPED_DIRECTORIES.contains(&file_name.as_str())
+        {
+            continue;
+        }
+
+        projects.extend(scan_projects(&entry.path(), depth - 1)?);
+    }
+
+    Ok(projects)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn finds_project_roots_without_looking_inside_them() {
+        let root = TempDir::new().unwrap();
+        let write = |file: &str, contents: &str| {
+            let path = root.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        };
+        write("api/Cargo.toml", "[package]\nname = \"shop-api\"\n");
+        write("api/crates/core/Cargo.toml", "[package]\nname = \"core\"\n");
+        write("apps/web/package.json", r#"{"name": "@shop/web"}"#);
+        write("apps/web/node_modules/react/package.json", "{}");
+        write(
+            "tools/lint/pyproject.toml",
+            "[tool.poetry]\nname = \"lint\"\n",
+        );
+        write("docs/README.md", "");
+
+        let projects = scan_projects(root.path(), 2).unwrap();
+
+        assert_eq!(
+            projects
+                .iter()
+                .map(|project| (project.name.as_str(), project.language.as_deref()))
+                .collect::<Vec<_>>(),
+            [
+                ("shop-api", Some("rust")),
+                ("@shop/web", Some("javascript")),
+                ("lint", Some("python")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use std::{io, path::Path, process::Command, sync::Mutex, time::Duration};
+
+use super::config::RegisteredProject;
+use crate::{
+    scripts::{self, ScriptOutput, ScriptStatus},
+    workers::for_each_parallel,
+};
+
+/// How a command went in one project
+pub struct ProjectRun<'a> {
+    pub project: &'a RegisteredProject,
+    /// Unset when the command couldn't be started
+    pub output: io::Result<ScriptOutput>,
+}
+
+impl ProjectRun<'_> {
+    pub fn status(&self) -> ScriptStatus {
+        match &self.output {
+            Ok(output) => output.status,
+            Err(_) => ScriptStatus::Failed,
+        }
+    }
+}
+
+/// Runs the command built by `command` in each project, `parallel` at a time, handing every run to
+/// `finished` as soon as it's done. Projects that haven't started yet are skipped once Ctrl-C is
+/// pressed.
+pub fn run_in_projects<'a, Build, Finished>(
+    projects: &'a [RegisteredProject],
+    parallel: usize,
+    timeout: Option<Duration>,
+    command: Build,
+    finished: Finished,
+) where
+    Build: Fn(&RegisteredProject) -> Command + Sync,
+    Finished: FnMut(ProjectRun<'a>) + Send,
+{
+    scripts::listen_for_interrupts();
+
+    // Keeps the output of projects finishing at the same time from interleaving
+    let finished = Mutex::new(finished);
+
+    for_each_parallel(projects, parallel, |_, project| {
+        if scripts::was_interrupted() {
+            return false;
+        }
+
+        let mut project_command = command(project);
+        project_command.envs(project.environment());
+        let output = scripts::run_script(project_command, Path::new(&project.path), timeout);
+
+        let mut finished = finished.lock().unwrap();
+        finished(ProjectRun { project, output });
+        true
+    });
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::scripts::Shell;
+
+    #[test]
+    fn runs_in_every_project_with_its_environment() {
+        let root = TempDir::new().unwrap();
+        let projects = ["api", "web", "worker"]
+            .iter()
+            .map(|name| {
+                let path = root.path().join(name);
+                std::fs::create_dir(&path).unwrap();
+                RegisteredProject::new(name, path.to_str().unwrap())
+            })
+            .collect::<Vec<RegisteredProject>>();
+
+        let mut runs = vec![];
+        run_in_projects(
+            &projects,
+            2,
+            None,
+            |_| {
+                Shell::Sh.inline(
+                    "test \"$(cd \"$EDNA_PROJECT_PATH\" && pwd -P)\" = \"$(pwd -P)\" && test \"$EDNA_PROJECT_NAME\" != web",
+                )
+            },
+            |run| runs.push((run.project.name.clone(), run.status())),
+        );
+        runs.sort_by(|first, second| first.0.cmp(&second.0));
+
+        assert_eq!(
+            runs,
+            [
+                (String::from("api"), ScriptStatus::Success),
+                (String::from("web"), ScriptStatus::Failed),
+                (String::from("worker"), ScriptStatus::Success),
+            ]
+        );
+    }
+}
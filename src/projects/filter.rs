@@ -0,0 +1,171 @@
+use std::{path::Path, str::FromStr};
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use super::config::RegisteredProject;
+
+/// Narrows down registered projects, where every criterion that's set has to match
+#[derive(Debug, Default)]
+pub struct ProjectFilter {
+    pub template: Option<String>,
+    /// Tags the project must all have
+    pub tags: Vec<String>,
+    /// Matched ignoring case
+    pub owner: Option<String>,
+    /// Metadata entries the project must all have
+    pub metadata: Vec<(String, String)>,
+    pub since: Option<DateTime<Utc>>,
+    /// Only keeps projects whose folder no longer exists
+    pub missing: bool,
+    /// Text looked for in the name, description, path, owner, tags and metadata values, ignoring
+    /// case
+    pub text: Option<String>,
+}
+
+impl ProjectFilter {
+    pub fn matches(&self, project: &RegisteredProject) -> bool {
+        let template_matches = self.template.as_ref().is_none_or(|template| {
+            project
+                .template
+                .as_ref()
+                .is_some_and(|used| used.eq_ignore_ascii_case(template))
+        });
+        let tags_match = self.tags.iter().all(|tag| project.tags.contains(tag));
+        let owner_matches = self.owner.as_ref().is_none_or(|owner| {
+            project
+                .owner
+                .as_ref()
+                .is_some_and(|project_owner| project_owner.eq_ignore_ascii_case(owner))
+        });
+        let metadata_matches = self
+            .metadata
+            .iter()
+            .all(|(key, value)| project.metadata.get(key) == Some(value));
+        let since_matches = self.since.is_none_or(|since| project.created >= since);
+        let missing_matches = !self.missing || !Path::new(&project.path).exists();
+        let text_matches = self.text.as_ref().is_none_or(|text| {
+            let text = text.to_lowercase();
+            [&project.name, &project.description, &project.path]
+                .into_iter()
+                .chain(&project.owner)
+                .chain(&project.tags)
+                .chain(project.metadata.values())
+                .any(|field| field.to_lowercase().contains(&text))
+        });
+
+        template_matches
+            && tags_match
+            && owner_matches
+            && metadata_matches
+            && since_matches
+            && missing_matches
+            && text_matches
+    }
+}
+
+/// Parses a date like `2024-05-31` or an RFC 3339 timestamp
+pub fn parse_since(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap().and_utc());
+    }
+
+    DateTime::parse_from_rfc3339(value)
+        .map(|date| date.with_timezone(&Utc))
+        .map_err(|_| format!("{} is not a date like 2024-05-31", value))
+}
+
+/// The order projects are listed in
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectSort {
+    #[default]
+    Name,
+    Created,
+    Path,
+    Template,
+}
+
+impl FromStr for ProjectSort {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "name" => Ok(ProjectSort::Name),
+            "created" => Ok(ProjectSort::Created),
+            "path" => Ok(ProjectSort::Path),
+            "template" => Ok(ProjectSort::Template),
+            _ => Err(format!("{} is not a way to sort projects", value)),
+        }
+    }
+}
+
+impl ProjectSort {
+    pub fn sort(self, projects: &mut [RegisteredProject]) {
+        match self {
+            ProjectSort::Name => projects.sort_by_key(|project| project.name.to_lowercase()),
+            ProjectSort::Created => projects.sort_by_key(|project| project.created),
+            ProjectSort::Path => projects.sort_by_key(|project| project.path.clone()),
+            ProjectSort::Template => projects.sort_by_key(|project| project.template.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(name: &str, template: Option<&str>, tags: &[&str]) -> RegisteredProject {
+        RegisteredProject {
+            description: String::from("An internal service"),
+            template: template.map(String::from),
+            created: parse_since("2024-05-31").unwrap(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..RegisteredProject::new(name, &format!("/work/{}", name))
+        }
+    }
+
+    #[test]
+    fn matches_every_criterion_that_is_set() {
+        let api = project("api", Some("rust-web"), &["backend", "team-a"]);
+        let web = project("web", None, &["frontend"]);
+
+        let filter = ProjectFilter {
+            template: Some(String::from("Rust-Web")),
+            tags: vec![String::from("backend")],
+            since: Some(parse_since("2024-01-01").unwrap()),
+            ..ProjectFilter::default()
+        };
+        assert!(filter.matches(&api));
+        assert!(!filter.matches(&web));
+
+        let search = ProjectFilter {
+            text: Some(String::from("INTERNAL")),
+            ..ProjectFilter::default()
+        };
+        assert!(search.matches(&web));
+
+        let mut payments = project("payments", None, &[]);
+        payments.owner = Some(String::from("Team-Payments"));
+        payments
+            .metadata
+            .insert(String::from("tier"), String::from("critical"));
+        let owned = ProjectFilter {
+            owner: Some(String::from("team-payments")),
+            metadata: vec![(String::from("tier"), String::from("critical"))],
+            ..ProjectFilter::default()
+        };
+        assert!(owned.matches(&payments));
+        assert!(!owned.matches(&api));
+
+        let search = ProjectFilter {
+            text: Some(String::from("critical")),
+            ..ProjectFilter::default()
+        };
+        assert!(search.matches(&payments));
+
+        let too_recent = ProjectFilter {
+            since: Some(parse_since("2024-06-01T00:00:00Z").unwrap()),
+            ..ProjectFilter::default()
+        };
+        assert!(!too_recent.matches(&api));
+    }
+}
@@ -0,0 +1,200 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use flate2::{Compression, write::GzEncoder};
+
+use super::config::RegisteredProject;
+use crate::file_system::copy::{CopyOptions, copy_fs_objects};
+
+/// Checks that a project's folder is one edna may archive, move or delete: an absolute path to a
+/// folder that is neither the filesystem's root nor holds edna's own files or other projects
+pub fn check_managed_path(
+    project: &RegisteredProject,
+    registry: &[RegisteredProject],
+) -> Result<PathBuf, String> {
+    let path = PathBuf::from(&project.path);
+    if !path.is_absolute() || path.parent().is_none() {
+        return Err(format!("{} is not a path edna manages.", project.path));
+    }
+
+    let canonical = fs::canonicalize(&path).map_err(|_| {
+        format!(
+            "The folder of {} no longer exists at {}.",
+            project.name, project.path
+        )
+    })?;
+    if !canonical.is_dir() {
+        return Err(format!("{} is not a folder.", project.path));
+    }
+
+    let working_directory = env::current_dir().and_then(fs::canonicalize);
+    if working_directory.is_ok_and(|directory| directory.starts_with(&canonical)) {
+        return Err(format!(
+            "{} holds edna's templates and registry, so it can't be changed.",
+            project.path
+        ));
+    }
+
+    let nested = registry.iter().find(|other| {
+        other.path != project.path
+            && fs::canonicalize(&other.path).is_ok_and(|other| other.starts_with(&canonical))
+    });
+    if let Some(nested) = nested {
+        return Err(format!(
+            "{} holds another registered project, {}, so it can't be changed.",
+            project.path, nested.name
+        ));
+    }
+
+    Ok(path)
+}
+
+/// Compresses a project's folder into a `.tar.gz` archive, with the folder at its root. A partly
+/// written archive is removed when compressing fails.
+pub fn archive_project(project_path: &Path, archive_path: &Path) -> io::Result<()> {
+    let archive = fs::File::create_new(archive_path)?;
+    let mut builder = tar::Builder::new(GzEncoder::new(archive, Compression::default()));
+    builder.follow_symlinks(false);
+
+    let folder_name = project_path.file_name().unwrap_or(project_path.as_os_str());
+    let written = builder
+        .append_dir_all(folder_name, project_path)
+        .and_then(|_| builder.into_inner()?.finish());
+    if let Err(error) = written {
+        let _ = fs::remove_file(archive_path);
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+/// Copies a project's folder as it is, `.ednaignore` and all, then removes the original. A partial
+/// copy is removed when the copy fails.
+fn move_by_copying(from: &Path, to: &Path) -> io::Result<()> {
+    let copied = copy_fs_objects(
+        from,
+        to,
+        &CopyOptions {
+            disable_ednaignore: true,
+            preserve_timestamps: true,
+            show_progress: true,
+            ..CopyOptions::default()
+        },
+    );
+    if let Err(error) = copied {
+        let _ = fs::remove_dir_all(to);
+        return Err(error);
+    }
+
+    fs::remove_dir_all(from)
+}
+
+/// Moves a project's folder, copying it when it's moved to another filesystem
+pub fn move_project(from: &Path, to: &Path) -> io::Result<()> {
+    if fs::exists(to)? {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", to.display()),
+        ));
+    }
+
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    match fs::rename(from, to) {
+        Err(error) if error.kind() == io::ErrorKind::CrossesDevices => move_by_copying(from, to),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn project(path: &Path) -> RegisteredProject {
+        RegisteredProject::new(
+            &path.file_name().unwrap().to_string_lossy(),
+            path.to_str().unwrap(),
+        )
+    }
+
+    #[test]
+    fn refuses_folders_holding_other_projects() {
+        let root = TempDir::new().unwrap();
+        fs::create_dir_all(root.path().join("outer/inner")).unwrap();
+        let registry = [
+            project(&root.path().join("outer")),
+            project(&root.path().join("outer/inner")),
+        ];
+
+        assert!(check_managed_path(&registry[0], &registry).is_err());
+        assert!(check_managed_path(&registry[1], &registry).is_ok());
+        let root_project = RegisteredProject {
+            path: String::from("/"),
+            ..registry[1].clone()
+        };
+        assert!(check_managed_path(&root_project, &registry).is_err());
+    }
+
+    #[test]
+    fn archives_and_moves_a_project() {
+        let root = TempDir::new().unwrap();
+        let project_path = root.path().join("api");
+        fs::create_dir_all(project_path.join("src")).unwrap();
+        fs::write(project_path.join("src/main.rs"), "fn main() {}").unwrap();
+
+        let archive_path = root.path().join("api.tar.gz");
+        archive_project(&project_path, &archive_path).unwrap();
+        let moved_path = root.path().join("services/api");
+        move_project(&project_path, &moved_path).unwrap();
+
+        let archive = flate2::read::GzDecoder::new(fs::File::open(archive_path).unwrap());
+        let entries = tar::Archive::new(archive)
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().to_path_buf())
+            .collect::<Vec<PathBuf>>();
+        assert!(entries.contains(&PathBuf::from("api/src/main.rs")));
+        assert!(moved_path.join("src/main.rs").is_file());
+        assert!(!project_path.exists());
+    }
+
+    #[test]
+    fn removes_the_archive_when_archiving_fails() {
+        let root = TempDir::new().unwrap();
+        let archive_path = root.path().join("api.tar.gz");
+
+        assert!(archive_project(&root.path().join("api"), &archive_path).is_err());
+        assert!(!archive_path.exists());
+    }
+
+    #[test]
+    fn moves_ignored_files_along_with_the_project() {
+        let root = TempDir::new().unwrap();
+        let project_path = root.path().join("api");
+        fs::create_dir(&project_path).unwrap();
+        fs::write(project_path.join(".ednaignore"), "*.log\n").unwrap();
+        fs::write(project_path.join("debug.log"), "started").unwrap();
+
+        // Moves across filesystems copy the folder, which a single temporary folder can't cause
+        let moved_path = root.path().join("moved");
+        fs::create_dir(&moved_path).unwrap();
+        move_by_copying(&project_path, &moved_path.join("api")).unwrap();
+
+        let moved_path = moved_path.join("api");
+        assert_eq!(
+            fs::read_to_string(moved_path.join(".ednaignore")).unwrap(),
+            "*.log\n"
+        );
+        assert_eq!(
+            fs::read_to_string(moved_path.join("debug.log")).unwrap(),
+            "started"
+        );
+        assert!(!project_path.exists());
+    }
+}
@@ -0,0 +1,75 @@
+use super::config::RegisteredProject;
+
+/// How similar a name has to be to the one asked for to count as a match
+const FUZZY_THRESHOLD: f64 = 0.8;
+
+/// Finds the projects a name refers to. An exact name wins, then a name that only differs in
+/// case, and otherwise every project whose name contains or resembles the one asked for.
+pub fn find_projects<'a>(
+    registry: &'a [RegisteredProject],
+    requested: &str,
+) -> Vec<&'a RegisteredProject> {
+    let exact = registry
+        .iter()
+        .filter(|project| project.name == requested)
+        .collect::<Vec<&RegisteredProject>>();
+    if !exact.is_empty() {
+        return exact;
+    }
+
+    let requested = requested.to_lowercase();
+    let same_case = registry
+        .iter()
+        .filter(|project| project.name.to_lowercase() == requested)
+        .collect::<Vec<&RegisteredProject>>();
+    if !same_case.is_empty() {
+        return same_case;
+    }
+
+    let mut scored = registry
+        .iter()
+        .map(|project| {
+            let name = project.name.to_lowercase();
+            let score = if name.contains(&requested) {
+                1.0
+            } else {
+                strsim::jaro_winkler(&requested, &name)
+            };
+            (score, project)
+        })
+        .filter(|(score, _)| *score >= FUZZY_THRESHOLD)
+        .collect::<Vec<(f64, &RegisteredProject)>>();
+
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.into_iter().map(|(_, project)| project).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry(names: &[&str]) -> Vec<RegisteredProject> {
+        names
+            .iter()
+            .map(|name| RegisteredProject::new(name, &format!("/work/{}", name)))
+            .collect()
+    }
+
+    fn names(projects: Vec<&RegisteredProject>) -> Vec<&str> {
+        projects
+            .into_iter()
+            .map(|project| project.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn prefers_exact_names_over_partial_ones() {
+        let registry = registry(&["api", "api-gateway", "Billing", "web"]);
+
+        assert_eq!(names(find_projects(&registry, "api")), ["api"]);
+        assert_eq!(names(find_projects(&registry, "billing")), ["Billing"]);
+        assert_eq!(names(find_projects(&registry, "gate")), ["api-gateway"]);
+        assert_eq!(names(find_projects(&registry, "webb")), ["web"]);
+        assert!(find_projects(&registry, "mobile").is_empty());
+    }
+}
@@ -0,0 +1,8 @@
+pub mod config;
+pub mod detect;
+pub mod exec;
+pub mod filter;
+pub mod lifecycle;
+pub mod lookup;
+pub mod status;
+pub mod tasks;
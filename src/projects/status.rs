@@ -0,0 +1,187 @@
+use std::{
+    fs, io,
+    path::Path,
+    process::{Command, Stdio},
+    sync::Mutex,
+};
+
+use chrono::{DateTime, FixedOffset};
+use serde::Serialize;
+
+use super::config::RegisteredProject;
+use crate::workers::{available_workers, for_each_parallel};
+
+/// The most projects inspected at once
+const MAX_STATUS_THREADS: usize = 8;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct GitStatus {
+    /// Unset when the repository has no commits yet
+    pub branch: Option<String>,
+    /// Whether there are uncommitted changes or untracked files
+    pub dirty: bool,
+    pub last_commit: Option<DateTime<FixedOffset>>,
+}
+
+/// The health of a registered project
+#[derive(Serialize, Debug, Clone)]
+pub struct ProjectStatus {
+    pub name: String,
+    pub path: String,
+    pub exists: bool,
+    pub archived: bool,
+    /// Unset for projects that aren't git repositories
+    pub git: Option<GitStatus>,
+    pub template: Option<String>,
+    pub template_version: Option<String>,
+    /// The version the project's template is at now
+    pub latest_template_version: Option<String>,
+    /// Whether the template has moved on since the project was created from it
+    pub outdated: bool,
+    /// The size of the project's files in bytes
+    pub disk_usage: Option<u64>,
+}
+
+/// Runs a git command in `directory` and returns what it printed, if it succeeded
+fn git(directory: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn git_status(directory: &Path) -> Option<GitStatus> {
+    // Only repositories of the project itself count, not one it happens to be inside of
+    if !directory.join(".git").exists() {
+        return None;
+    }
+
+    Some(GitStatus {
+        branch: git(directory, &["symbolic-ref", "--short", "HEAD"]),
+        dirty: git(directory, &["status", "--porcelain"])
+            .is_some_and(|changes| !changes.is_empty()),
+        last_commit: git(directory, &["log", "-1", "--format=%cI"])
+            .and_then(|date| DateTime::parse_from_rfc3339(&date).ok()),
+    })
+}
+
+/// Adds up the sizes of the files in `directory`, without following links
+pub fn disk_usage(directory: &Path) -> io::Result<u64> {
+    let mut total = 0;
+    for entry in fs::read_dir(directory)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        total += if file_type.is_dir() {
+            disk_usage(&entry.path())?
+        } else {
+            entry.metadata()?.len()
+        };
+    }
+
+    Ok(total)
+}
+
+/// The numbers in a version like `1.2.0` or `v2.0-beta`, if it has any
+fn version_numbers(version: &str) -> Option<Vec<u64>> {
+    version
+        .trim_start_matches('v')
+        .split('.')
+        .map(|part| {
+            let digits = part
+                .chars()
+                .take_while(|character| character.is_ascii_digit())
+                .collect::<String>();
+            digits.parse::<u64>().ok()
+        })
+        .collect()
+}
+
+/// Whether `current` is older than `latest`, treating versions that can't be compared as behind
+/// when they differ
+pub fn is_behind(current: &str, latest: &str) -> bool {
+    match (version_numbers(current), version_numbers(latest)) {
+        (Some(mut current), Some(mut latest)) => {
+            // Missing parts count as zero, so that `1.0` and `1.0.0` are the same version
+            let length = current.len().max(latest.len());
+            current.resize(length, 0);
+            latest.resize(length, 0);
+            current < latest
+        }
+        _ => current != latest,
+    }
+}
+
+pub fn project_status(
+    project: &RegisteredProject,
+    latest_template_version: Option<String>,
+) -> ProjectStatus {
+    let path = Path::new(&project.path);
+    let exists = path.is_dir();
+    let outdated = match (&project.template_version, &latest_template_version) {
+        (Some(current), Some(latest)) => is_behind(current, latest),
+        _ => false,
+    };
+
+    ProjectStatus {
+        name: project.name.clone(),
+        path: project.path.clone(),
+        exists,
+        archived: project.archived.is_some(),
+        git: exists.then(|| git_status(path)).flatten(),
+        template: project.template.clone(),
+        template_version: project.template_version.clone(),
+        latest_template_version,
+        outdated,
+        disk_usage: exists.then(|| disk_usage(path).ok()).flatten(),
+    }
+}
+
+/// Inspects every project on a bounded pool of threads, keeping the projects' order
+pub fn project_statuses<Latest: Fn(&RegisteredProject) -> Option<String> + Sync>(
+    projects: &[RegisteredProject],
+    latest_template_version: Latest,
+) -> Vec<ProjectStatus> {
+    let statuses: Mutex<Vec<Option<ProjectStatus>>> = Mutex::new(vec![None; projects.len()]);
+
+    for_each_parallel(
+        projects,
+        available_workers(MAX_STATUS_THREADS),
+        |index, project| {
+            let status = project_status(project, latest_template_version(project));
+            statuses.lock().unwrap()[index] = Some(status);
+            true
+        },
+    );
+
+    statuses
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_versions_by_their_numbers() {
+        assert!(is_behind("1.2.0", "1.10.0"));
+        assert!(is_behind("v1.0", "1.0.1"));
+        assert!(!is_behind("2.0.0", "1.9.9"));
+        assert!(!is_behind("1.0.0", "1.0.0"));
+        assert!(!is_behind("1.0", "1.0.0"));
+        assert!(!is_behind("1.0.0", "1.0"));
+        assert!(is_behind("alpha", "beta"));
+    }
+}
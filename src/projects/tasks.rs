@@ -0,0 +1,60 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::templates::config::TemplateScript;
+
+/// The tasks a generated project keeps in its `edna.config.json`
+#[derive(Deserialize, Debug, Default)]
+pub struct ProjectTasks {
+    /// The shell used by tasks that don't set their own
+    #[serde(default)]
+    pub shell: Option<String>,
+    #[serde(default)]
+    pub tasks: BTreeMap<String, TemplateScript>,
+}
+
+/// Finds the `edna.config.json` in `directory` or the closest folder above it, returning the folder
+/// it's in along with its tasks
+pub fn find_project_tasks(directory: &Path) -> io::Result<Option<(PathBuf, ProjectTasks)>> {
+    for folder in directory.ancestors() {
+        let config_path = folder.join("edna.config.json");
+        if config_path.is_file() {
+            let tasks = serde_json::from_str(&fs::read_to_string(config_path)?)?;
+            return Ok(Some((folder.to_path_buf(), tasks)));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn finds_the_tasks_of_the_closest_project() {
+        let project = TempDir::new().unwrap();
+        fs::create_dir_all(project.path().join("src/bin")).unwrap();
+        fs::write(
+            project.path().join("edna.config.json"),
+            r#"{"shell": "bash", "tasks": {"test": "cargo test", "lint": {"run": "cargo clippy", "shell": "sh"}}}"#,
+        )
+        .unwrap();
+
+        let (root, tasks) = find_project_tasks(&project.path().join("src/bin"))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(root, project.path());
+        assert_eq!(tasks.shell.as_deref(), Some("bash"));
+        assert_eq!(tasks.tasks["test"].label(), "cargo test");
+        assert_eq!(tasks.tasks["lint"].shell(), Some("sh"));
+    }
+}
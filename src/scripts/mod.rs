@@ -0,0 +1,226 @@
+use std::{
+    io::{self, Read},
+    path::Path,
+    process::{Child, Command, Stdio},
+    sync::{
+        Once,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+const TERMINATE_GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+static INTERRUPT_HANDLER: Once = Once::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptStatus {
+    Success,
+    Failed,
+    TimedOut,
+    Interrupted,
+}
+
+pub struct ScriptOutput {
+    pub status: ScriptStatus,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+/// Catches Ctrl-C so that running scripts can be stopped instead of the whole process
+pub fn listen_for_interrupts() {
+    INTERRUPT_HANDLER.call_once(|| {
+        let _ = ctrlc::set_handler(|| INTERRUPTED.store(true, Ordering::SeqCst));
+    });
+}
+
+pub fn was_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// The interpreter used to run a script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Shell {
+    Sh,
+    Bash,
+    Zsh,
+    Pwsh,
+    Python,
+    Cmd,
+    Other(String),
+}
+
+impl Shell {
+    pub fn from_name(name: &str) -> Shell {
+        match name.to_lowercase().as_str() {
+            "sh" => Shell::Sh,
+            "bash" => Shell::Bash,
+            "zsh" => Shell::Zsh,
+            "pwsh" | "powershell" => Shell::Pwsh,
+            "python" | "python3" => Shell::Python,
+            "cmd" => Shell::Cmd,
+            _ => Shell::Other(name.to_string()),
+        }
+    }
+
+    /// Infers the interpreter for a script file from its extension
+    pub fn for_file<At: AsRef<Path>>(path: At) -> Option<Shell> {
+        let extension = path.as_ref().extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "sh" => Some(Shell::Sh),
+            "bash" => Some(Shell::Bash),
+            "zsh" => Some(Shell::Zsh),
+            "ps1" => Some(Shell::Pwsh),
+            "py" => Some(Shell::Python),
+            "bat" | "cmd" => Some(Shell::Cmd),
+            _ => None,
+        }
+    }
+
+    fn program(&self) -> &str {
+        match self {
+            Shell::Sh => "sh",
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Pwsh => "pwsh",
+            Shell::Python => {
+                if cfg!(target_os = "windows") {
+                    "python"
+                } else {
+                    "python3"
+                }
+            }
+            Shell::Cmd => "cmd",
+            Shell::Other(name) => name,
+        }
+    }
+
+    /// Builds a command that runs `script` as inline source
+    pub fn inline(&self, script: &str) -> Command {
+        let mut command = Command::new(self.program());
+        match self {
+            Shell::Pwsh => command.args(["-NoProfile", "-Command", script]),
+            Shell::Cmd => command.args(["/C", script]),
+            _ => command.args(["-c", script]),
+        };
+        command
+    }
+
+    /// Builds a command that runs the script file at `path`
+    pub fn file<At: AsRef<Path>>(&self, path: At) -> Command {
+        let mut command = Command::new(self.program());
+        match self {
+            Shell::Pwsh => command.args(["-NoProfile", "-File"]),
+            Shell::Cmd => command.arg("/C"),
+            _ => &mut command,
+        };
+        command.arg(path.as_ref());
+        command
+    }
+}
+
+impl Default for Shell {
+    fn default() -> Self {
+        if cfg!(target_os = "windows") {
+            Shell::Cmd
+        } else {
+            Shell::Sh
+        }
+    }
+}
+
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+        buffer
+    })
+}
+
+#[cfg(unix)]
+fn terminate(child: &mut Child) {
+    // Scripts run in their own process group, so signal the whole group to reach grandchildren
+    let group = -(child.id() as i32);
+    unsafe {
+        libc::kill(group, libc::SIGTERM);
+    }
+
+    let started = Instant::now();
+    while started.elapsed() < TERMINATE_GRACE_PERIOD {
+        if let Ok(Some(_)) = child.try_wait() {
+            break;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+
+    unsafe {
+        libc::kill(group, libc::SIGKILL);
+    }
+    let _ = child.wait();
+}
+
+#[cfg(not(unix))]
+fn terminate(child: &mut Child) {
+    let _ = Command::new("taskkill")
+        .args(["/F", "/T", "/PID", &child.id().to_string()])
+        .output();
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Runs a script command, stopping it once it exceeds `timeout` or Ctrl-C is pressed
+pub fn run_script<At: AsRef<Path>>(
+    mut command: Command,
+    current_dir: At,
+    timeout: Option<Duration>,
+) -> io::Result<ScriptOutput> {
+    command
+        .current_dir(current_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    let mut child = command.spawn()?;
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+    let started = Instant::now();
+
+    let status = loop {
+        if let Some(exit_status) = child.try_wait()? {
+            break if exit_status.success() {
+                ScriptStatus::Success
+            } else {
+                ScriptStatus::Failed
+            };
+        }
+
+        if was_interrupted() {
+            terminate(&mut child);
+            break ScriptStatus::Interrupted;
+        }
+
+        if timeout.is_some_and(|limit| started.elapsed() >= limit) {
+            terminate(&mut child);
+            break ScriptStatus::TimedOut;
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    Ok(ScriptOutput {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
@@ -1,12 +1,28 @@
 use std::{
+    collections::BTreeMap,
     fs,
     io::{self, Write},
-    path, process,
+    path::{self, Path},
+    process::{self, Command},
 };
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::json;
 
-use crate::file_system::copy_fs_objects;
+use crate::{
+    file_system::{
+        content::{BomPolicy, LineEndings, TextFormatOverride},
+        copy::{CopyOptions, CopySummary, SymlinkPolicy, copy_fs_objects},
+        patch::FilePatch,
+    },
+    scripts::Shell,
+};
+
+/// The `target` of templates that create whole projects
+pub const PROJECT_TARGET: &str = "project";
+/// The `target` of templates that add files and scripts to an existing project
+pub const FRAGMENT_TARGET: &str = "fragment";
 
 pub struct TemplatesMetadata {
     pub directory: String,
@@ -17,6 +33,10 @@ pub struct TemplatesMetadata {
 pub struct CreatedTemplateConfig {
     pub target: String,
     pub name: String,
+    /// The registered name, alias or path of a template whose files, excludes, variables and scripts
+    /// this one is layered on top of, where relative paths start at this template's folder
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
     #[serde(default, deserialize_with = "deserialize_optional_field")]
     pub author: String,
     #[serde(default, deserialize_with = "deserialize_optional_field")]
@@ -26,15 +46,121 @@ pub struct CreatedTemplateConfig {
     #[serde(default, deserialize_with = "deserialize_optional_field")]
     pub exclude_paths: Vec<String>,
     #[serde(default, deserialize_with = "deserialize_optional_field")]
-    pub scripts: Vec<String>,
+    pub scripts: Vec<TemplateScript>,
     #[serde(default, deserialize_with = "deserialize_optional_field")]
     pub exclude_config: bool,
+    /// The maximum number of seconds all scripts may run for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// Removes the generated project when it's cancelled with Ctrl-C, whether while its files are
+    /// copied or while its scripts run
+    #[serde(default, deserialize_with = "deserialize_optional_field")]
+    pub rollback_on_cancel: bool,
+    /// The shell used to run scripts that don't set their own, e.g. `bash`, `pwsh` or `python`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+    /// Other names the template can be referred to by
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Whether symbolic links are preserved, followed or skipped when copying
+    #[serde(default, deserialize_with = "deserialize_optional_field")]
+    pub symlinks: SymlinkPolicy,
+    /// Keeps the modification times of the template's files in the generated project
+    #[serde(default, deserialize_with = "deserialize_optional_field")]
+    pub preserve_timestamps: bool,
+    /// Values for `{{ key }}` placeholders, alongside the project's name, description, version and author
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<String, String>,
+    /// Gitignore-style patterns of the only files placeholders are filled in for
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub render: Vec<String>,
+    /// Gitignore-style patterns of files that are always copied as they are
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub raw: Vec<String>,
+    /// The line endings text files are converted to, left as they are when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line_endings: Option<LineEndings>,
+    /// Whether text files keep, lose or gain a UTF-8 byte order mark
+    #[serde(default, deserialize_with = "deserialize_optional_field")]
+    pub bom: BomPolicy,
+    /// Line endings and byte order mark handling for the files matching some patterns
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub text_overrides: Vec<TextFormatOverride>,
+    /// Sub-projects generated inside the project, each from its own template
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<TemplateComponent>,
+    /// Changes made to files once they're copied, which are skipped for files that already have them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patches: Vec<FilePatch>,
+    /// Commands like `build` or `test` that `edna run` runs in the generated project, where
+    /// script files are relative to the project's root
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tasks: BTreeMap<String, TemplateScript>,
+}
+
+/// A sub-project of a multi-project template, named after the last part of its path
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TemplateComponent {
+    /// Where the sub-project is created, relative to the project's root, e.g. `apps/api`
+    pub path: String,
+    /// The registered name, alias or path of the template the sub-project is created from, where
+    /// relative paths start at the folder of the multi-project template
+    pub template: String,
+    /// The sub-project's own answers, which may use the project's placeholders. The project's
+    /// answers are used for the ones left out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Values for placeholders, taking precedence over the component template's variables
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<String, String>,
+}
+
+/// A script entry, either a plain command, a command with its own settings or a file in the template
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum TemplateScript {
+    Command(String),
+    Detailed {
+        run: String,
+        /// The maximum number of seconds this script may run for
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        shell: Option<String>,
+    },
+    File {
+        /// The path of the script relative to the template's root
+        file: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        shell: Option<String>,
+        /// Leaves the script out of the generated project
+        #[serde(default, deserialize_with = "deserialize_optional_field")]
+        exclude: bool,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RegisteredTemplate {
     pub name: String,
     pub path: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    #[serde(default, skip_serializing_if = "TemplateUsage::is_unused")]
+    pub usage: TemplateUsage,
+}
+
+/// How often and how recently a template was used to create projects
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TemplateUsage {
+    pub count: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<DateTime<Utc>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -61,8 +187,14 @@ impl TemplatesConfig {
         path_buffer.to_str().unwrap().to_string()
     }
 
+    /// Reads the registry without failing, for commands that can do without templates
+    pub fn read(metadata: &TemplatesMetadata) -> Option<TemplatesConfig> {
+        let file = fs::File::open(Self::get_path(metadata)).ok()?;
+        serde_json::from_reader(file).ok()
+    }
+
     pub fn load(metadata: &TemplatesMetadata) -> TemplatesConfig {
-        let file = fs::File::open(Self::get_path(&metadata)).unwrap();
+        let file = fs::File::open(Self::get_path(metadata)).unwrap();
         let config: TemplatesConfig = serde_json::from_reader(file).unwrap();
         config
     }
@@ -71,59 +203,194 @@ impl TemplatesConfig {
         template: &RegisteredTemplate,
         metadata: &TemplatesMetadata,
     ) -> Result<(), io::Error> {
-        let mut config = Self::load(&metadata);
-        let path = Self::get_path(&metadata);
+        let mut config = Self::load(metadata);
+        let path = Self::get_path(metadata);
         config.registry.push(template.clone());
         let file_writer = fs::File::create(path).unwrap();
-        let _ = serde_json::to_writer(file_writer, &config).unwrap();
+        serde_json::to_writer(file_writer, &config).unwrap();
 
         Ok(())
     }
+
+    /// Counts a use of the registered template at `template_path`, doing nothing for templates
+    /// that aren't registered
+    pub fn record_use(template_path: &str, metadata: &TemplatesMetadata) -> io::Result<()> {
+        let mut config = Self::load(metadata);
+        let template_path = path::absolute(template_path)?;
+        let Some(template) = config
+            .registry
+            .iter_mut()
+            .find(|template| path::absolute(&template.path).ok() == Some(template_path.clone()))
+        else {
+            return Ok(());
+        };
+
+        template.usage.count += 1;
+        template.usage.last_used = Some(Utc::now());
+
+        let file_writer = fs::File::create(Self::get_path(metadata))?;
+        serde_json::to_writer(file_writer, &config).map_err(io::Error::from)
+    }
+}
+
+impl TemplateUsage {
+    pub fn is_unused(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Ranks templates used both often and lately first, weighing the number of uses by how long
+    /// ago the last one was
+    pub fn score(&self, now: DateTime<Utc>) -> f64 {
+        let Some(last_used) = self.last_used else {
+            return 0.0;
+        };
+
+        let recency = match (now - last_used).num_days() {
+            ..=1 => 4.0,
+            2..=7 => 2.0,
+            8..=30 => 1.0,
+            _ => 0.5,
+        };
+        f64::from(self.count) * recency
+    }
+}
+
+impl TemplateScript {
+    /// The command or file the script runs, used when reporting progress
+    pub fn label(&self) -> &str {
+        match self {
+            TemplateScript::Command(run) | TemplateScript::Detailed { run, .. } => run,
+            TemplateScript::File { file, .. } => file,
+        }
+    }
+
+    pub fn timeout(&self) -> Option<u64> {
+        match self {
+            TemplateScript::Command(_) => None,
+            TemplateScript::Detailed { timeout, .. } | TemplateScript::File { timeout, .. } => {
+                *timeout
+            }
+        }
+    }
+
+    pub fn shell(&self) -> Option<&str> {
+        match self {
+            TemplateScript::Command(_) => None,
+            TemplateScript::Detailed { shell, .. } | TemplateScript::File { shell, .. } => {
+                shell.as_deref()
+            }
+        }
+    }
+
+    /// Builds the command that runs the script, with files found relative to `root` and
+    /// `default_shell` used when the script doesn't choose one
+    pub fn command(&self, default_shell: Option<&str>, root: &str) -> Command {
+        let configured_shell = self.shell().or(default_shell).map(Shell::from_name);
+
+        match self {
+            TemplateScript::File { file, shell, .. } => {
+                let file_path = path::absolute(Path::new(root).join(file)).unwrap();
+                // A shell set on the script wins, then the file's extension, then the default shell
+                let shell = shell
+                    .as_deref()
+                    .map(Shell::from_name)
+                    .or_else(|| Shell::for_file(&file_path))
+                    .or(configured_shell)
+                    .unwrap_or_default();
+
+                shell.file(file_path)
+            }
+            _ => configured_shell.unwrap_or_default().inline(self.label()),
+        }
+    }
 }
 
 impl CreatedTemplateConfig {
+    /// The patch that records the template's tasks, and the shell they run in, in the generated
+    /// project's own `edna.config.json`
+    pub fn tasks_patch(&self) -> Option<FilePatch> {
+        if self.tasks.is_empty() {
+            return None;
+        }
+
+        let mut value = json!({ "tasks": self.tasks });
+        if let Some(shell) = &self.shell {
+            value["shell"] = json!(shell);
+        }
+
+        Some(FilePatch::Merge {
+            file: String::from("edna.config.json"),
+            value,
+        })
+    }
+
     pub fn create_config(path: &String, config: &CreatedTemplateConfig) -> Result<(), io::Error> {
         let file_writer = fs::File::create(path).unwrap();
-        let _ = serde_json::to_writer(file_writer, config).unwrap();
+        serde_json::to_writer(file_writer, config).unwrap();
         Ok(())
     }
 
+    /// Creates and registers a template, copying it from `source` unless that's empty. Files ignored
+    /// by the source's `.gitignore` are only copied when `respect_gitignore` is off, and `.git` is
+    /// never copied.
     pub fn create_template(
         output: &String,
         source: &String,
         config: &CreatedTemplateConfig,
         metadata: &TemplatesMetadata,
-    ) -> Result<(), io::Error> {
-        let template_exists = fs::exists(&output).unwrap();
+        respect_gitignore: bool,
+    ) -> Result<CopySummary, io::Error> {
+        let template_exists = fs::exists(output).unwrap();
         if template_exists {
             eprintln!("The template \"{}\" already exists!", &output);
             process::exit(1);
         }
 
-        if source == "" {
-            let _ = fs::create_dir(output).unwrap();
+        let summary = if source.is_empty() {
+            fs::create_dir(output).unwrap();
+            CopySummary::default()
         } else {
-            let _ = copy_fs_objects(source, output, &config.exclude_paths);
-        }
+            let mut exclude = config.exclude_paths.clone();
+            exclude.push(String::from(".git"));
 
-        let _ = Self::create_config(
-            &String::from(format!("{}/{}", output, "edna.config.json")),
-            config,
-        );
+            copy_fs_objects(
+                source,
+                output,
+                &CopyOptions {
+                    exclude: &exclude,
+                    respect_gitignore,
+                    symlinks: config.symlinks,
+                    preserve_timestamps: config.preserve_timestamps,
+                    show_progress: true,
+                    ..CopyOptions::default()
+                },
+            )?
+        };
+
+        let _ = Self::create_config(&format!("{}/{}", output, "edna.config.json"), config);
 
         let _ = TemplatesConfig::register_template(
             &RegisteredTemplate {
                 name: config.name.clone(),
                 path: output.to_string(),
+                aliases: config.aliases.clone(),
+                usage: TemplateUsage::default(),
             },
             metadata,
         );
 
-        Ok(())
+        Ok(summary)
     }
 }
 
 impl RegisteredTemplate {
+    /// Reads the config of the template at `template_path` as it is, without creating or checking
+    /// it, for listings that shouldn't stop on a broken template
+    pub fn read_config(template_path: &str) -> Option<CreatedTemplateConfig> {
+        let file = fs::File::open(Path::new(template_path).join("edna.config.json")).ok()?;
+        serde_json::from_reader(file).ok()
+    }
+
     pub fn load_config(template_path: &String) -> CreatedTemplateConfig {
         let resolved_path = format!("{}/{}", &template_path, "edna.config.json");
         let config_exists = fs::exists(&resolved_path).unwrap();
@@ -153,16 +420,29 @@ impl RegisteredTemplate {
         let file = fs::File::open(&resolved_path).unwrap();
         let mut config: CreatedTemplateConfig = serde_json::from_reader(file).unwrap();
 
-        if config.target != "project" {
+        if config.target != PROJECT_TARGET && config.target != FRAGMENT_TARGET {
             eprintln!(
-                "Invalid template config for {}. The variant must be `project`",
-                &resolved_path
+                "Invalid template config for {}. The variant must be `{}` or `{}`",
+                &resolved_path, PROJECT_TARGET, FRAGMENT_TARGET
             );
             process::exit(1);
         }
 
         if config.exclude_config {
-            config.exclude_paths.push(resolved_path);
+            config.exclude_paths.push(String::from("/edna.config.json"));
+        }
+
+        for script in &config.scripts {
+            if let TemplateScript::File {
+                file,
+                exclude: true,
+                ..
+            } = script
+            {
+                config
+                    .exclude_paths
+                    .push(format!("/{}", file.trim_start_matches("./")));
+            }
         }
 
         config
@@ -0,0 +1,265 @@
+use std::{fmt, fs, path::Path};
+
+use crate::file_system::{content::BomPolicy, copy::SymlinkPolicy};
+
+use super::{
+    config::{CreatedTemplateConfig, RegisteredTemplate, TemplateScript},
+    resolver::{ResolveError, ResolvedTemplate, TemplateResolver},
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ExtendsError {
+    /// The template a config extends couldn't be found
+    Parent {
+        template: String,
+        error: ResolveError,
+    },
+    /// The templates extend each other, listed from the one generated from
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for ExtendsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtendsError::Parent { template, error } => {
+                write!(
+                    f,
+                    "The template {} extends a missing template. {}",
+                    template, error
+                )
+            }
+            ExtendsError::Cycle(chain) => write!(
+                f,
+                "The templates extend each other in a cycle: {}.",
+                chain.join(" -> ")
+            ),
+        }
+    }
+}
+
+/// A template folder along with its own config
+#[derive(Debug, Clone)]
+pub struct TemplateLayer {
+    pub path: String,
+    pub config: CreatedTemplateConfig,
+}
+
+/// A template along with the templates it extends, outermost parent first, so that each layer is
+/// copied on top of the ones before it
+#[derive(Debug, Clone)]
+pub struct TemplateLayers {
+    pub layers: Vec<TemplateLayer>,
+}
+
+impl TemplateLayers {
+    /// Follows the `extends` of the template at `template_path` through the registry, with relative
+    /// paths taken from the folder of the template that extends them
+    pub fn resolve(
+        template_path: &str,
+        resolver: &TemplateResolver,
+    ) -> Result<TemplateLayers, ExtendsError> {
+        let mut layers = vec![];
+        let mut visited = vec![];
+        let mut next = Some(template_path.to_string());
+
+        while let Some(path) = next.take() {
+            let config = RegisteredTemplate::load_config(&path);
+            let canonical_path =
+                fs::canonicalize(&path).unwrap_or_else(|_| Path::new(&path).into());
+            if visited.contains(&canonical_path) {
+                let mut chain = layers
+                    .iter()
+                    .map(|layer: &TemplateLayer| layer.config.name.clone())
+                    .collect::<Vec<String>>();
+                chain.push(config.name);
+                return Err(ExtendsError::Cycle(chain));
+            }
+            visited.push(canonical_path);
+
+            if let Some(parent) = &config.extends {
+                match resolver.resolve_relative_to(parent, &path) {
+                    Ok(ResolvedTemplate::Directory(parent_path)) => next = Some(parent_path),
+                    Ok(ResolvedTemplate::Empty) => {}
+                    Err(error) => {
+                        return Err(ExtendsError::Parent {
+                            template: config.name,
+                            error,
+                        });
+                    }
+                }
+            }
+
+            layers.push(TemplateLayer { path, config });
+        }
+
+        layers.reverse();
+        Ok(TemplateLayers { layers })
+    }
+
+    /// The config of the template itself, with the variables, scripts, components, patches, tasks
+    /// and file patterns of its parents layered underneath its own. Scripts run parents first, and
+    /// the file scripts of parents point into their own folder. Settings are taken from the
+    /// closest layer that sets them, where the BOM and symlink policies count as set when they
+    /// differ from their default and the boolean flags when they're on.
+    pub fn config(&self) -> CreatedTemplateConfig {
+        let (template, parents) = self.layers.split_last().expect("a template has one layer");
+        let mut config = CreatedTemplateConfig {
+            variables: Default::default(),
+            scripts: vec![],
+            render: vec![],
+            raw: vec![],
+            text_overrides: vec![],
+            components: vec![],
+            patches: vec![],
+            tasks: Default::default(),
+            symlinks: Default::default(),
+            preserve_timestamps: false,
+            rollback_on_cancel: false,
+            bom: Default::default(),
+            ..template.config.clone()
+        };
+
+        for (index, layer) in self.layers.iter().enumerate() {
+            let layer_config = &layer.config;
+            config.variables.extend(layer_config.variables.clone());
+            if index < parents.len() {
+                config.scripts.extend(
+                    layer_config
+                        .scripts
+                        .iter()
+                        .map(|script| script.resolved_against(&layer.path)),
+                );
+            } else {
+                config.scripts.extend(layer_config.scripts.clone());
+            }
+            config.render.extend(layer_config.render.clone());
+            config.raw.extend(layer_config.raw.clone());
+            config
+                .text_overrides
+                .extend(layer_config.text_overrides.clone());
+            config.components.extend(layer_config.components.clone());
+            config.patches.extend(layer_config.patches.clone());
+            config.tasks.extend(layer_config.tasks.clone());
+            config.timeout = layer_config.timeout.or(config.timeout);
+            config.shell = layer_config.shell.clone().or(config.shell);
+            config.line_endings = layer_config.line_endings.or(config.line_endings);
+            if layer_config.bom != BomPolicy::default() {
+                config.bom = layer_config.bom;
+            }
+            if layer_config.symlinks != SymlinkPolicy::default() {
+                config.symlinks = layer_config.symlinks;
+            }
+            config.preserve_timestamps |= layer_config.preserve_timestamps;
+            config.rollback_on_cancel |= layer_config.rollback_on_cancel;
+        }
+
+        config
+    }
+
+    /// The paths left out when copying the layer at `index`, which are its own excludes and those
+    /// of the templates extending it
+    pub fn exclude_paths(&self, index: usize) -> Vec<String> {
+        self.layers[index..]
+            .iter()
+            .flat_map(|layer| layer.config.exclude_paths.clone())
+            .collect()
+    }
+}
+
+impl TemplateScript {
+    /// The script with its file, if it has one, resolved against `template_path`
+    fn resolved_against(&self, template_path: &str) -> TemplateScript {
+        match self {
+            TemplateScript::File {
+                file,
+                timeout,
+                shell,
+                exclude,
+            } => TemplateScript::File {
+                file: Path::new(template_path)
+                    .join(file)
+                    .to_string_lossy()
+                    .to_string(),
+                timeout: *timeout,
+                shell: shell.clone(),
+                exclude: *exclude,
+            },
+            script => script.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn detects_templates_extending_each_other() {
+        let templates = TempDir::new().unwrap();
+        let mut registry = vec![];
+        for (name, parent) in [("web", "base"), ("base", "web")] {
+            let path = templates.path().join(name);
+            fs::create_dir(&path).unwrap();
+            fs::write(
+                path.join("edna.config.json"),
+                format!(r#"{{"target": "project", "name": "{name}", "extends": "{parent}"}}"#),
+            )
+            .unwrap();
+            registry.push(RegisteredTemplate {
+                name: name.to_string(),
+                path: path.to_str().unwrap().to_string(),
+                aliases: vec![],
+                usage: Default::default(),
+            });
+        }
+
+        let template_path = registry[0].path.clone();
+        let error =
+            TemplateLayers::resolve(&template_path, &TemplateResolver::new(registry)).unwrap_err();
+
+        assert_eq!(
+            error,
+            ExtendsError::Cycle(vec![
+                String::from("web"),
+                String::from("base"),
+                String::from("web")
+            ])
+        );
+    }
+
+    #[test]
+    fn extends_relative_paths_and_inherits_settings() {
+        let templates = TempDir::new().unwrap();
+        for (name, config) in [
+            (
+                "base",
+                r#"{"target": "project", "name": "base", "bom": "strip", "symlinks": "skip",
+                    "preserve_timestamps": true, "rollback_on_cancel": true}"#,
+            ),
+            (
+                "web",
+                r#"{"target": "project", "name": "web", "extends": "../base", "bom": "add"}"#,
+            ),
+        ] {
+            let path = templates.path().join(name);
+            fs::create_dir(&path).unwrap();
+            fs::write(path.join("edna.config.json"), config).unwrap();
+        }
+
+        let template_path = templates.path().join("web");
+        let layers = TemplateLayers::resolve(
+            template_path.to_str().unwrap(),
+            &TemplateResolver::new(vec![]),
+        )
+        .unwrap();
+        let config = layers.config();
+
+        assert_eq!(layers.layers.len(), 2);
+        assert_eq!(config.bom, BomPolicy::Add);
+        assert_eq!(config.symlinks, SymlinkPolicy::Skip);
+        assert!(config.preserve_timestamps);
+        assert!(config.rollback_on_cancel);
+    }
+}
@@ -1,24 +1,79 @@
-use std::fs;
+use std::{cmp::Ordering, fs};
 
-use config::{RegisteredTemplate, TemplatesConfig, TemplatesMetadata};
+use chrono::Utc;
+use config::{PROJECT_TARGET, RegisteredTemplate, TemplatesConfig, TemplatesMetadata};
 
 pub mod config;
+pub mod inheritance;
+pub mod resolver;
 
+/// Orders templates by how often and how recently they were used, keeping the registry's order
+/// for templates that score the same
+pub fn sort_by_usage(templates: &mut [RegisteredTemplate]) {
+    let now = Utc::now();
+    templates.sort_by(|first, second| {
+        second
+            .usage
+            .score(now)
+            .partial_cmp(&first.usage.score(now))
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+/// The project templates whose folders exist, most used first, followed by an entry for no
+/// template. Fragments are left out since they can't create a project on their own.
 pub fn get_templates(metadata: &TemplatesMetadata) -> Vec<RegisteredTemplate> {
-    let config_details = TemplatesConfig::load(&metadata);
+    let config_details = TemplatesConfig::load(metadata);
+
+    let mut registered_templates = config_details
+        .registry
+        .into_iter()
+        .filter(|entry| fs::metadata(&entry.path).is_ok_and(|metadata| metadata.is_dir()))
+        .filter(|entry| {
+            RegisteredTemplate::read_config(&entry.path)
+                .is_none_or(|config| config.target == PROJECT_TARGET)
+        })
+        .collect::<Vec<RegisteredTemplate>>();
+    sort_by_usage(&mut registered_templates);
 
-    let mut registered_templates: Vec<RegisteredTemplate> = Vec::new();
     registered_templates.push(RegisteredTemplate {
         name: String::from("(No template)"),
         path: String::from(""),
+        aliases: vec![],
+        usage: Default::default(),
     });
 
-    for entry in config_details.registry {
-        let metadata = fs::metadata(&entry.path).unwrap();
-        if metadata.is_dir() {
-            registered_templates.push(entry);
-        }
-    }
-
     registered_templates
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::templates::config::TemplateUsage;
+
+    #[test]
+    fn puts_templates_used_often_and_lately_first() {
+        let now = Utc::now();
+        let mut templates = [
+            ("unused", 0, None),
+            ("old-favourite", 6, Some(now - Duration::days(90))),
+            ("yesterday", 1, Some(now - Duration::hours(20))),
+            ("this-week", 3, Some(now - Duration::days(3))),
+        ]
+        .map(|(name, count, last_used)| RegisteredTemplate {
+            name: name.to_string(),
+            path: String::new(),
+            aliases: vec![],
+            usage: TemplateUsage { count, last_used },
+        });
+
+        sort_by_usage(&mut templates);
+
+        assert_eq!(
+            templates.map(|template| template.name),
+            ["this-week", "yesterday", "old-favourite", "unused"]
+        );
+    }
+}
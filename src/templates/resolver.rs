@@ -0,0 +1,281 @@
+use std::{fmt, path};
+
+use super::config::{RegisteredTemplate, TemplatesConfig, TemplatesMetadata};
+
+const SUGGESTION_THRESHOLD: f64 = 0.8;
+
+/// The template a project is generated from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolvedTemplate {
+    /// Creates an empty project directory
+    Empty,
+    /// Copies the template directory at the given path
+    Directory(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ResolveError {
+    NotFound {
+        requested: String,
+        suggestions: Vec<String>,
+    },
+    Ambiguous {
+        requested: String,
+        matches: Vec<String>,
+    },
+    MissingDirectory {
+        name: String,
+        path: String,
+    },
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::NotFound {
+                requested,
+                suggestions,
+            } => {
+                write!(
+                    f,
+                    "The template or path {} does not exist! Please supply a valid folder path or a registered template.",
+                    requested
+                )?;
+
+                if !suggestions.is_empty() {
+                    write!(f, " Did you mean {}?", suggestions.join(", "))?;
+                }
+
+                Ok(())
+            }
+            ResolveError::Ambiguous { requested, matches } => write!(
+                f,
+                "The template {} is ambiguous, it could be any of {}.",
+                requested,
+                matches.join(", ")
+            ),
+            ResolveError::MissingDirectory { name, path } => write!(
+                f,
+                "The template {} is registered at {}, but that folder no longer exists.",
+                name, path
+            ),
+        }
+    }
+}
+
+/// Finds templates by registered name, alias or path
+pub struct TemplateResolver {
+    registry: Vec<RegisteredTemplate>,
+}
+
+impl TemplateResolver {
+    pub fn new(registry: Vec<RegisteredTemplate>) -> TemplateResolver {
+        TemplateResolver { registry }
+    }
+
+    pub fn load(metadata: &TemplatesMetadata) -> TemplateResolver {
+        Self::new(TemplatesConfig::load(metadata).registry)
+    }
+
+    /// Resolves `none`, a registered name or alias (falling back to a case-insensitive match) or a
+    /// path to a template folder
+    pub fn resolve(&self, requested: &str) -> Result<ResolvedTemplate, ResolveError> {
+        self.resolve_from(requested, path::Path::new(""))
+    }
+
+    /// Resolves like `resolve`, but with relative paths taken from `base`, e.g. the folder of the
+    /// template that refers to `requested`
+    pub fn resolve_relative_to(
+        &self,
+        requested: &str,
+        base: &str,
+    ) -> Result<ResolvedTemplate, ResolveError> {
+        self.resolve_from(requested, path::Path::new(base))
+    }
+
+    fn resolve_from(
+        &self,
+        requested: &str,
+        base: &path::Path,
+    ) -> Result<ResolvedTemplate, ResolveError> {
+        if requested.eq_ignore_ascii_case("none") {
+            return Ok(ResolvedTemplate::Empty);
+        }
+
+        if let Some(template) = self.find(|key| key == requested) {
+            return Self::resolve_registered(template);
+        }
+
+        let requested_path = base.join(requested);
+        if requested_path.is_dir() {
+            let template_path = path::absolute(requested_path).unwrap();
+            return Ok(ResolvedTemplate::Directory(
+                template_path.to_str().unwrap().to_string(),
+            ));
+        }
+
+        let matches = self
+            .registry
+            .iter()
+            .filter(|template| Self::keys(template).any(|key| key.eq_ignore_ascii_case(requested)))
+            .collect::<Vec<&RegisteredTemplate>>();
+
+        match matches.as_slice() {
+            [template] => Self::resolve_registered(template),
+            [] => Err(ResolveError::NotFound {
+                requested: requested.to_string(),
+                suggestions: self.suggestions(requested),
+            }),
+            _ => Err(ResolveError::Ambiguous {
+                requested: requested.to_string(),
+                matches: matches
+                    .iter()
+                    .map(|template| template.name.clone())
+                    .collect(),
+            }),
+        }
+    }
+
+    /// Resolves a registry entry using its registered path, where an empty path means no template
+    pub fn resolve_registered(
+        template: &RegisteredTemplate,
+    ) -> Result<ResolvedTemplate, ResolveError> {
+        if template.path.is_empty() {
+            return Ok(ResolvedTemplate::Empty);
+        }
+
+        let template_path = path::absolute(&template.path).unwrap();
+        if !template_path.is_dir() {
+            return Err(ResolveError::MissingDirectory {
+                name: template.name.clone(),
+                path: template.path.clone(),
+            });
+        }
+
+        Ok(ResolvedTemplate::Directory(
+            template_path.to_str().unwrap().to_string(),
+        ))
+    }
+
+    fn keys(template: &RegisteredTemplate) -> impl Iterator<Item = &String> {
+        std::iter::once(&template.name).chain(template.aliases.iter())
+    }
+
+    fn find<P: Fn(&String) -> bool>(&self, predicate: P) -> Option<&RegisteredTemplate> {
+        self.registry
+            .iter()
+            .find(|template| Self::keys(template).any(&predicate))
+    }
+
+    /// Names and aliases that look like `requested`, closest first
+    fn suggestions(&self, requested: &str) -> Vec<String> {
+        let requested = requested.to_lowercase();
+        let mut scored = self
+            .registry
+            .iter()
+            .flat_map(Self::keys)
+            .map(|key| (strsim::jaro_winkler(&requested, &key.to_lowercase()), key))
+            .filter(|(score, _)| *score >= SUGGESTION_THRESHOLD)
+            .collect::<Vec<(f64, &String)>>();
+
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        scored.into_iter().map(|(_, key)| key.clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn registered(name: &str, path: &str, aliases: &[&str]) -> RegisteredTemplate {
+        RegisteredTemplate {
+            name: name.to_string(),
+            path: path.to_string(),
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+            usage: Default::default(),
+        }
+    }
+
+    fn template_dir(root: &TempDir, name: &str) -> String {
+        let path = root.path().join(name);
+        fs::create_dir_all(&path).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn resolves_none_to_an_empty_project() {
+        let resolver = TemplateResolver::new(vec![]);
+        assert_eq!(resolver.resolve("None"), Ok(ResolvedTemplate::Empty));
+    }
+
+    #[test]
+    fn resolves_names_and_aliases_to_the_registered_path() {
+        let root = TempDir::new().unwrap();
+        let path = template_dir(&root, "somewhere-else");
+        let resolver = TemplateResolver::new(vec![registered("rust-cli", &path, &["cli"])]);
+
+        let expected = Ok(ResolvedTemplate::Directory(path));
+        assert_eq!(resolver.resolve("rust-cli"), expected);
+        assert_eq!(resolver.resolve("cli"), expected);
+        assert_eq!(resolver.resolve("Rust-CLI"), expected);
+    }
+
+    #[test]
+    fn resolves_paths_to_template_folders() {
+        let root = TempDir::new().unwrap();
+        let path = template_dir(&root, "unregistered");
+        let resolver = TemplateResolver::new(vec![]);
+
+        assert_eq!(
+            resolver.resolve(&path),
+            Ok(ResolvedTemplate::Directory(path))
+        );
+    }
+
+    #[test]
+    fn suggests_close_names_when_nothing_matches() {
+        let root = TempDir::new().unwrap();
+        let path = template_dir(&root, "rust-cli");
+        let resolver = TemplateResolver::new(vec![
+            registered("rust-cli", &path, &[]),
+            registered("python", &path, &[]),
+        ]);
+
+        assert_eq!(
+            resolver.resolve("rust-clu"),
+            Err(ResolveError::NotFound {
+                requested: String::from("rust-clu"),
+                suggestions: vec![String::from("rust-cli")],
+            })
+        );
+    }
+
+    #[test]
+    fn reports_ambiguous_case_insensitive_matches() {
+        let root = TempDir::new().unwrap();
+        let path = template_dir(&root, "web");
+        let resolver = TemplateResolver::new(vec![
+            registered("Web", &path, &[]),
+            registered("WEB", &path, &[]),
+        ]);
+
+        assert!(matches!(
+            resolver.resolve("web"),
+            Err(ResolveError::Ambiguous { .. })
+        ));
+    }
+
+    #[test]
+    fn reports_registered_templates_whose_folder_is_gone() {
+        let resolver = TemplateResolver::new(vec![registered("gone", "/no/such/template", &[])]);
+
+        assert!(matches!(
+            resolver.resolve("gone"),
+            Err(ResolveError::MissingDirectory { .. })
+        ));
+    }
+}
@@ -0,0 +1,67 @@
+use std::{
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    thread,
+};
+
+/// The number of threads the machine can run at once, but at most `max`
+pub fn available_workers(max: usize) -> usize {
+    thread::available_parallelism()
+        .map_or(1, |parallelism| parallelism.get())
+        .min(max)
+}
+
+/// Calls `work` with each item and its index on up to `workers` threads, which pick up the next
+/// item as soon as they're done with one. Every thread stops once `work` returns false.
+pub fn for_each_parallel<'a, Item, Work>(items: &'a [Item], workers: usize, work: Work)
+where
+    Item: Sync,
+    Work: Fn(usize, &'a Item) -> bool + Sync,
+{
+    let next_item = AtomicUsize::new(0);
+    let stopped = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        for _ in 0..workers.min(items.len()).max(1) {
+            scope.spawn(|| {
+                while !stopped.load(Ordering::Relaxed) {
+                    let index = next_item.fetch_add(1, Ordering::Relaxed);
+                    let Some(item) = items.get(index) else {
+                        break;
+                    };
+
+                    if !work(index, item) {
+                        stopped.store(true, Ordering::Relaxed);
+                    }
+                }
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[test]
+    fn visits_every_item_once_until_told_to_stop() {
+        let items = (0..100).collect::<Vec<usize>>();
+        let visited = Mutex::new(vec![]);
+        for_each_parallel(&items, 4, |index, item| {
+            assert_eq!(index, *item);
+            visited.lock().unwrap().push(*item);
+            true
+        });
+        let mut visited = visited.into_inner().unwrap();
+        visited.sort();
+        assert_eq!(visited, items);
+
+        let visited = AtomicUsize::new(0);
+        for_each_parallel(&items, 1, |index, _| {
+            visited.fetch_add(1, Ordering::Relaxed);
+            index < 9
+        });
+        assert_eq!(visited.into_inner(), 10);
+    }
+}